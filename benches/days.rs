@@ -0,0 +1,23 @@
+// Times every day which has an input in inputs/, or in $AOC_INPUTS. Numbers after -- only bench those days,
+// like cargo bench -- 19 20. The bench subcommand does the same and can save and compare runs.
+use std::env;
+use std::path::PathBuf;
+use code_advent_2022::bench::{self, Budget};
+use code_advent_2022::solution::SOLUTIONS;
+
+fn main() {
+    let inputs_dir = env::var_os("AOC_INPUTS").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("inputs"));
+    // cargo also passes --bench, so anything which isn't a day is ignored
+    let mut days: Vec<u8> = env::args().skip(1).filter_map(|arg| arg.parse().ok()).collect();
+    if days.is_empty() {
+        days = SOLUTIONS.iter().map(|puzzle| puzzle.day()).collect();
+    }
+
+    println!("{}", bench::header(false));
+    let results = bench::bench_days(&inputs_dir, &days, &Budget::default(), |day, steps| {
+        println!("{}", bench::format_day(day, steps, None, 0.0));
+    });
+    if results.is_empty() {
+        println!("no inputs found in {}", inputs_dir.display());
+    }
+}
@@ -0,0 +1,276 @@
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use crate::common::{Coord, Grid};
+
+// One picture of a simulation, drawn top row first
+pub struct Frame {
+    pub grid: Grid<char>,
+    // what a following viewport keeps on screen, like the falling rock in day 17
+    pub focus: Option<Coord>,
+    pub caption: String,
+}
+
+// Simulations call this after every step. They hand over a way to build the frame rather than the
+// frame itself, so solving normally or skipping frames doesn't pay for drawing them.
+pub type OnFrame<'a> = dyn FnMut(&dyn Fn() -> Frame) + 'a;
+
+pub fn no_frames(_frame: &dyn Fn() -> Frame) {}
+
+// The window of a frame which is on screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Viewport {
+    pub rows: usize,
+    pub columns: usize,
+    pub follow: bool,
+    origin: Option<Coord>,
+}
+
+impl Viewport {
+    pub fn new(rows: usize, columns: usize, follow: bool) -> Viewport {
+        Viewport { rows, columns, follow, origin: None }
+    }
+
+    // Moves the window for the next frame. Following only scrolls once the focus gets near an edge,
+    // otherwise the window stays on the top left of the grid.
+    pub fn place(&mut self, grid: &Grid<char>, focus: Option<Coord>) -> Coord {
+        let (rows, columns) = (self.rows as i64, self.columns as i64);
+        let mut origin = match (self.origin, focus) {
+            (Some(origin), Some(focus)) if self.follow => {
+                let margin = (rows / 4, columns / 4);
+                let row = if focus.0 < origin.0 + margin.0 || focus.0 >= origin.0 + rows - margin.0 {
+                    focus.0 - rows / 2
+                } else {
+                    origin.0
+                };
+                let col = if focus.1 < origin.1 + margin.1 || focus.1 >= origin.1 + columns - margin.1 {
+                    focus.1 - columns / 2
+                } else {
+                    origin.1
+                };
+                (row, col)
+            },
+            (None, Some(focus)) if self.follow => (focus.0 - rows / 2, focus.1 - columns / 2),
+            _ => grid.mins(),
+        };
+
+        // don't scroll past the edges of the grid
+        let (mins, maxes) = (grid.mins(), grid.maxes());
+        origin.0 = origin.0.min(maxes.0 - rows + 1).max(mins.0);
+        origin.1 = origin.1.min(maxes.1 - columns + 1).max(mins.1);
+        self.origin = Some(origin);
+        origin
+    }
+
+    pub fn crop(&mut self, frame: &Frame) -> Vec<String> {
+        let origin = self.place(&frame.grid, frame.focus);
+        let maxes = frame.grid.maxes();
+        (origin.0..(origin.0 + self.rows as i64).min(maxes.0 + 1)).map(|row| {
+            (origin.1..(origin.1 + self.columns as i64).min(maxes.1 + 1)).map(|col| *frame.grid.get_or_fill((row, col))).collect()
+        }).collect()
+    }
+}
+
+// The terminal can't keep up with more than this, so faster playback skips frames
+const MAX_DRAWS_PER_SECOND: f64 = 60.0;
+
+const HELP: &str = "space pause, n step, +/- speed, f follow, q quit";
+
+// Plays frames in the terminal as they arrive, at fps simulation steps a second. Keys only work when
+// stdin is a terminal, and frames are printed one after another when stdout isn't.
+pub struct Player {
+    viewport: Viewport,
+    fps: f64,
+    paused: bool,
+    quit: bool,
+    keys: bool,
+    ansi: bool,
+    steps: u64,
+    deadline: Instant,
+    status: String,
+}
+
+impl Player {
+    pub fn new(fps: f64, viewport: Option<(usize, usize)>, follow: bool) -> Player {
+        let ansi = io::stdout().is_terminal();
+        let (rows, columns) = match (viewport, terminal::size()) {
+            (Some(viewport), _) => viewport,
+            // leave a line for the status
+            (None, Ok((columns, rows))) if ansi => ((rows as usize).saturating_sub(2).max(1), columns as usize),
+            (None, _) => (40, 120),
+        };
+        let keys = io::stdin().is_terminal() && terminal::enable_raw_mode().is_ok();
+        if ansi {
+            // alternate screen and hidden cursor, undone on drop
+            print!("\x1b[?1049h\x1b[?25l");
+        }
+
+        Player {
+            viewport: Viewport::new(rows, columns, follow),
+            fps,
+            paused: false,
+            quit: false,
+            keys,
+            ansi,
+            steps: 0,
+            deadline: Instant::now(),
+            status: String::new(),
+        }
+    }
+
+    pub fn show(&mut self, frame: &dyn Fn() -> Frame) {
+        if self.quit {
+            return;
+        }
+
+        self.steps += 1;
+        let skip = (self.fps / MAX_DRAWS_PER_SECOND).ceil().max(1.0) as u64;
+        if self.paused || self.steps.is_multiple_of(skip) {
+            self.draw(&frame());
+        }
+        self.wait();
+    }
+
+    fn draw(&mut self, frame: &Frame) {
+        let lines = self.viewport.crop(frame);
+        self.status = format!("{} | step {} | {} fps{}", frame.caption, self.steps, self.fps, if self.viewport.follow { " | following" } else { "" });
+
+        let mut out = String::new();
+        if self.ansi {
+            out.push_str("\x1b[H");
+            for line in lines {
+                out.push_str(&line);
+                out.push_str("\x1b[K\r\n");
+            }
+            out.push_str("\x1b[J");
+            out.push_str(&self.status_line());
+        } else {
+            out.push_str(&frame.caption);
+            out.push('\n');
+            for line in lines {
+                out.push_str(&line);
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(out.as_bytes()).and_then(|_| stdout.flush()).unwrap_or_else(|_| self.quit = true);
+    }
+
+    fn status_line(&self) -> String {
+        format!("\x1b[7m{}{} | {}\x1b[0m\x1b[K", self.status, if self.paused { " | paused" } else { "" }, HELP)
+    }
+
+    fn redraw_status(&self) {
+        if self.ansi {
+            print!("\r{}", self.status_line());
+            io::stdout().flush().ok();
+        }
+    }
+
+    // Waits for this step's turn, or for a key to step or unpause while paused
+    fn wait(&mut self) {
+        let now = Instant::now();
+        self.deadline = if now > self.deadline + Duration::from_secs(1) {
+            // fell behind, probably while paused, so don't rush to catch up
+            now
+        } else {
+            self.deadline
+        } + Duration::from_secs_f64(1.0 / self.fps);
+
+        loop {
+            if self.quit {
+                return;
+            }
+            let timeout = if self.paused {
+                Duration::from_millis(250)
+            } else {
+                let timeout = self.deadline.saturating_duration_since(Instant::now());
+                if timeout.is_zero() {
+                    return;
+                }
+                timeout
+            };
+
+            if !self.keys {
+                thread::sleep(timeout);
+                continue;
+            }
+            if !event::poll(timeout).unwrap_or(false) {
+                continue;
+            }
+            if let Ok(Event::Key(key)) = event::read() {
+                if self.handle_key(key) {
+                    return;
+                }
+            }
+        }
+    }
+
+    // true when the key steps to the next frame
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('n') | KeyCode::Right if self.paused => return true,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.fps = (self.fps * 2.0).min(100000.0),
+            KeyCode::Char('-') => self.fps = (self.fps / 2.0).max(0.25),
+            KeyCode::Char('f') => self.viewport.follow = !self.viewport.follow,
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            _ => return false,
+        }
+        self.redraw_status();
+        false
+    }
+}
+
+impl Drop for Player {
+    fn drop(&mut self) {
+        if self.keys {
+            terminal::disable_raw_mode().ok();
+        }
+        if self.ansi {
+            print!("\x1b[?25h\x1b[?1049l");
+            io::stdout().flush().ok();
+        }
+    }
+}
+
+// Parses a viewport size like 40x120, rows first
+pub fn parse_viewport(viewport: &str) -> Result<(usize, usize), String> {
+    match viewport.split_once('x') {
+        Some((rows, columns)) => match (rows.parse(), columns.parse()) {
+            (Ok(rows), Ok(columns)) if rows > 0 && columns > 0 => Ok((rows, columns)),
+            _ => Err(format!("expected ROWSxCOLUMNS, found {:?}", viewport)),
+        },
+        None => Err(format!("expected ROWSxCOLUMNS, found {:?}", viewport)),
+    }
+}
+
+#[test]
+fn test_viewport() {
+    let grid = Grid::from_rows(&(0..10).map(|row| (0..10).map(|col| if (row + col) % 2 == 0 { '#' } else { '.' }).collect()).collect::<Vec<Vec<char>>>(), ' ');
+    let frame = |focus| Frame { grid: grid.clone(), focus, caption: String::new() };
+
+    let mut viewport = Viewport::new(4, 3, false);
+    assert_eq!(viewport.crop(&frame(Some((9, 9)))), vec!["#.#", ".#.", "#.#", ".#."]);
+
+    // following scrolls to the focus but not past the bottom right corner
+    let mut viewport = Viewport::new(4, 3, true);
+    assert_eq!(viewport.place(&grid, Some((5, 5))), (3, 4));
+    assert_eq!(viewport.place(&grid, Some((5, 6))), (3, 4));
+    assert_eq!(viewport.place(&grid, Some((9, 9))), (6, 7));
+    assert_eq!(viewport.crop(&frame(Some((9, 9)))), vec![".#.", "#.#", ".#.", "#.#"]);
+
+    // a viewport bigger than the grid shows all of it
+    assert_eq!(Viewport::new(20, 20, true).crop(&frame(Some((0, 0)))).len(), 10);
+
+    assert_eq!(parse_viewport("40x120"), Ok((40, 120)));
+    assert!(parse_viewport("40").is_err());
+    assert!(parse_viewport("0x3").is_err());
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use serde_json::{Map, Value};
+use crate::common::{input_name, read_input};
+use crate::runner::{format_duration, input_path, panic_message, without_panic_output};
+use crate::solution::{Part, Puzzle, SOLUTIONS};
+
+// Summary of the timings of one step, like parsing day 20 or solving its part 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let secs: Vec<f64> = samples.iter().map(|sample| sample.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len().max(1) as f64;
+        // sample standard deviation, which is zero for a single sample
+        let variance = match secs.len() {
+            0 | 1 => 0.0,
+            n => secs.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (n - 1) as f64,
+        };
+        Stats {
+            samples: samples.len(),
+            mean: Duration::from_secs_f64(mean),
+            min: samples.iter().min().copied().unwrap_or_default(),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    Parse,
+    Solve(Part),
+}
+
+impl Step {
+    // Keys in the results JSON, parts are numbers like in the answers manifest
+    fn key(self) -> String {
+        match self {
+            Step::Parse => "parse".to_string(),
+            Step::Solve(part) => part.number().to_string(),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Step> {
+        match key {
+            "parse" => Some(Step::Parse),
+            _ => key.parse().ok().and_then(Part::from_number).map(Step::Solve),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // pad so that the step lines up in the table
+        match self {
+            Step::Parse => f.pad("parse"),
+            Step::Solve(part) => f.pad(&format!("part {}", part.number())),
+        }
+    }
+}
+
+// Timings of every step of every day benched, keyed by day
+pub type Results = BTreeMap<u8, Vec<(Step, Stats)>>;
+
+// How long to keep timing a step for
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Budget {
+    pub samples: usize,
+    pub max_time: Duration,
+}
+
+impl Default for Budget {
+    fn default() -> Budget {
+        Budget { samples: 10, max_time: Duration::from_secs(5) }
+    }
+}
+
+// Times f until there are enough samples or the time is up. Slow steps like day 19 still get one sample.
+fn measure<T>(budget: &Budget, mut f: impl FnMut() -> T) -> Stats {
+    let start = Instant::now();
+    let mut samples = Vec::new();
+    while samples.is_empty() || (samples.len() < budget.samples && start.elapsed() < budget.max_time) {
+        let sample_start = Instant::now();
+        black_box(f());
+        samples.push(sample_start.elapsed());
+    }
+    Stats::from_samples(&samples)
+}
+
+// Times parsing the input, not counting reading it, and then each part against one parsed input
+pub fn bench_day(puzzle: &dyn Puzzle, input: &Path, budget: &Budget) -> Result<Vec<(Step, Stats)>, String> {
+    let file_path = input.to_string_lossy();
+    let contents = read_input(&file_path).map_err(|err| err.to_string())?;
+
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let parsed = puzzle.parse(&contents).map_err(|err| err.in_file(input_name(&file_path)).to_string())?;
+        let mut steps = vec![(Step::Parse, measure(budget, || puzzle.parse(&contents)))];
+        for part in puzzle.parts() {
+            if let Some(Err(err)) = parsed.solve(*part) {
+                return Err(err.in_file(input_name(&file_path)).to_string());
+            }
+            steps.push((Step::Solve(*part), measure(budget, || parsed.solve(*part))));
+        }
+        Ok(steps)
+    })).unwrap_or_else(|payload| Err(panic_message(payload)))
+}
+
+// Benches every day in days which has an input in inputs_dir, calling on_day as each one finishes since
+// the whole lot can take a while. Days which fail are left out of the results.
+pub fn bench_days(inputs_dir: &Path, days: &[u8], budget: &Budget, mut on_day: impl FnMut(u8, &Result<Vec<(Step, Stats)>, String>)) -> Results {
+    without_panic_output(|| {
+        let mut results = Results::new();
+        for puzzle in SOLUTIONS.iter().filter(|puzzle| days.contains(&puzzle.day())) {
+            let input = input_path(inputs_dir, puzzle.day());
+            if !input.is_file() {
+                continue;
+            }
+            let steps = bench_day(*puzzle, &input, budget);
+            on_day(puzzle.day(), &steps);
+            if let Ok(steps) = steps {
+                results.insert(puzzle.day(), steps);
+            }
+        }
+        results
+    })
+}
+
+pub fn find_stats(results: &Results, day: u8, step: Step) -> Option<&Stats> {
+    results.get(&day)?.iter().find(|(other, _stats)| *other == step).map(|(_step, stats)| stats)
+}
+
+// How the mean moved since a previous run, as a percentage of the old mean
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Faster(f64),
+    Slower(f64),
+    // within the threshold or the noise of the two runs
+    Same(f64),
+}
+
+pub fn compare(before: &Stats, after: &Stats, threshold: f64) -> Change {
+    let (before_mean, after_mean) = (before.mean.as_secs_f64(), after.mean.as_secs_f64());
+    let percent = if before_mean > 0.0 { (after_mean - before_mean) / before_mean * 100.0 } else { 0.0 };
+    let noise = (before.stddev + after.stddev).as_secs_f64();
+    if percent.abs() < threshold || (after_mean - before_mean).abs() <= noise {
+        Change::Same(percent)
+    } else if percent < 0.0 {
+        Change::Faster(percent)
+    } else {
+        Change::Slower(percent)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Faster(percent) => write!(f, "{:+.1}% faster", percent),
+            Change::Slower(percent) => write!(f, "{:+.1}% slower", percent),
+            Change::Same(percent) => write!(f, "{:+.1}%", percent),
+        }
+    }
+}
+
+pub fn header(comparing: bool) -> String {
+    let header = format!("{:>3}  {:<6}  {:>9}  {:>9}  {:>9}  {:>7}", "Day", "Step", "Mean", "Min", "Stddev", "Samples");
+    if comparing {
+        format!("{}  {:>9}  Change", header, "Before")
+    } else {
+        header
+    }
+}
+
+// A row of the table under header, with the change since before when comparing against a previous run
+fn format_row(day: u8, step: Step, stats: &Stats, before: Option<&Stats>, threshold: f64) -> String {
+    let row = format!("{:>3}  {:<6}  {:>9}  {:>9}  {:>9}  {:>7}", day, step, format_duration(stats.mean), format_duration(stats.min), format_duration(stats.stddev), stats.samples);
+    match before {
+        Some(before) => format!("{}  {:>9}  {}", row, format_duration(before.mean), compare(before, stats, threshold)),
+        None => row,
+    }
+}
+
+// The rows for a day, or why it couldn't be benched
+pub fn format_day(day: u8, steps: &Result<Vec<(Step, Stats)>, String>, previous: Option<&Results>, threshold: f64) -> String {
+    match steps {
+        Ok(steps) => steps.iter().map(|(step, stats)| {
+            format_row(day, *step, stats, previous.and_then(|previous| find_stats(previous, day, *step)), threshold)
+        }).collect::<Vec<String>>().join("\n"),
+        Err(err) => format!("{:>3}  FAILED {}", day, err),
+    }
+}
+
+fn nanos(duration: Duration) -> Value {
+    Value::from(duration.as_nanos() as u64)
+}
+
+// Stored as JSON like {"20": {"parse": {"samples": 10, "mean_ns": ...}, "1": {...}, "2": {...}}}
+pub fn results_to_json(results: &Results) -> String {
+    let days: Map<String, Value> = results.iter().map(|(day, steps)| {
+        let steps: Map<String, Value> = steps.iter().map(|(step, stats)| {
+            let mut fields = Map::new();
+            fields.insert("samples".to_string(), Value::from(stats.samples));
+            fields.insert("mean_ns".to_string(), nanos(stats.mean));
+            fields.insert("min_ns".to_string(), nanos(stats.min));
+            fields.insert("stddev_ns".to_string(), nanos(stats.stddev));
+            (step.key(), Value::Object(fields))
+        }).collect();
+        (day.to_string(), Value::Object(steps))
+    }).collect();
+    serde_json::to_string_pretty(&Value::Object(days)).unwrap()
+}
+
+pub fn parse_results(contents: &str) -> Result<Results, String> {
+    let value: Value = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+    let days = value.as_object().ok_or("expected an object keyed by day")?;
+
+    let mut results = Results::new();
+    for (day_key, steps) in days {
+        let day: u8 = day_key.parse().map_err(|_| format!("expected a day number, found {:?}", day_key))?;
+        let steps = steps.as_object().ok_or_else(|| format!("expected an object keyed by step for day {}", day))?;
+        for (step_key, fields) in steps {
+            let step = Step::from_key(step_key).ok_or_else(|| format!("expected parse or a part number for day {}, found {:?}", day, step_key))?;
+            let field = |name: &str| fields.get(name).and_then(Value::as_u64).ok_or_else(|| format!("expected a number for {} of day {} {}", name, day, step));
+            let stats = Stats {
+                samples: field("samples")? as usize,
+                mean: Duration::from_nanos(field("mean_ns")?),
+                min: Duration::from_nanos(field("min_ns")?),
+                stddev: Duration::from_nanos(field("stddev_ns")?),
+            };
+            results.entry(day).or_default().push((step, stats));
+        }
+    }
+    // JSON objects come back sorted by key, which would put parse last
+    for steps in results.values_mut() {
+        steps.sort_by_key(|(step, _stats)| *step);
+    }
+    Ok(results)
+}
+
+pub fn load_results(path: &Path) -> Result<Results, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    parse_results(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+#[test]
+fn test_bench() {
+    let stats = Stats::from_samples(&[Duration::from_millis(2), Duration::from_millis(4), Duration::from_millis(6)]);
+    assert_eq!(stats.samples, 3);
+    assert_eq!(stats.mean, Duration::from_millis(4));
+    assert_eq!(stats.min, Duration::from_millis(2));
+    assert_eq!(stats.stddev, Duration::from_millis(2));
+    assert_eq!(Stats::from_samples(&[Duration::from_millis(5)]).stddev, Duration::ZERO);
+
+    let budget = Budget { samples: 3, max_time: Duration::from_secs(5) };
+    assert_eq!(measure(&budget, || 1).samples, 3);
+    // out of time, but there's always one sample
+    assert_eq!(measure(&Budget { samples: 3, max_time: Duration::ZERO }, || 1).samples, 1);
+
+    let before = Stats { mean: Duration::from_millis(4), stddev: Duration::from_micros(100), ..stats };
+    let slower = Stats { mean: Duration::from_millis(5), ..before };
+    let noisy = Stats { stddev: Duration::from_millis(2), ..slower };
+    assert_eq!(compare(&before, &slower, 5.0), Change::Slower(25.0));
+    assert_eq!(compare(&slower, &before, 5.0), Change::Faster(-20.0));
+    assert_eq!(compare(&before, &slower, 30.0), Change::Same(25.0));
+    assert_eq!(compare(&before, &noisy, 5.0), Change::Same(25.0));
+    assert_eq!(Change::Slower(25.0).to_string(), "+25.0% slower");
+
+    let results: Results = [(20, vec![(Step::Parse, stats), (Step::Solve(Part::Two), slower)])].into_iter().collect();
+    assert_eq!(parse_results(&results_to_json(&results)), Ok(results.clone()));
+    assert_eq!(find_stats(&results, 20, Step::Solve(Part::Two)), Some(&slower));
+    assert_eq!(find_stats(&results, 20, Step::Solve(Part::One)), None);
+    assert!(parse_results("{\"20\": {\"3\": {}}}").is_err());
+}
@@ -1,119 +1,615 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
 use std::fs;
+use std::hash::Hash;
+use std::io::{self, Read};
+use std::ops::{Deref, Index, IndexMut};
+use std::panic;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use array2d::Array2D;
+use crate::error::ParseError;
 
+// A line of input which remembers where it came from so parse errors can point at it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub number: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+impl Deref for Line {
+    type Target = str;
 
-pub fn get_trimmed_lines(file_path: &str) -> Vec<String> {
-    let file_contents = fs::read_to_string(file_path).unwrap();
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
 
-    file_contents.split("\n").map(|line| line.trim()).filter(|line| !line.is_empty()).map(|line| line.to_string()).collect()
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
 }
 
-pub fn parse_digit_grid(file_path: &str) -> Array2D<u8> {
-    let rows: Vec<Vec<u8>> = get_trimmed_lines(file_path).iter().map(|line| {
-        let row: Vec<u8> = line.chars().map(|c| {
-            let digit: u8 = c.to_digit(10).unwrap() as u8;
-            digit
-        }).collect();
+impl Line {
+    pub fn new(number: usize, text: &str) -> Line {
+        Line { number, column: 1, text: text.to_string() }
+    }
+
+    pub fn trimmed(&self) -> Line {
+        let start = self.text.len() - self.text.trim_start().len();
+        Line {
+            number: self.number,
+            column: self.column + self.text[..start].chars().count(),
+            text: self.text.trim().to_string(),
+        }
+    }
+
+    pub fn error(&self, expected: &str) -> ParseError {
+        ParseError::new(self.number, self.column, &self.text, expected)
+    }
 
-        row
-    }).collect();
-    let ret = Array2D::from_rows(
-        &rows
-    ).unwrap();
-    ret
+    // part should be a slice of this line, otherwise the error points at the start of the line
+    pub fn error_at(&self, part: &str, expected: &str) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize).wrapping_sub(start);
+        let column = if offset <= self.text.len() && self.text.is_char_boundary(offset) {
+            self.column + self.text[..offset].chars().count()
+        } else {
+            self.column
+        };
+        ParseError::new(self.number, column, part, expected)
+    }
+
+    pub fn parse_at<T: FromStr>(&self, part: &str, expected: &str) -> Result<T, ParseError> {
+        part.parse().map_err(|_| self.error_at(part, expected))
+    }
 }
 
-type CoordType = (usize, usize);
-type ValueType = u8;
-type CostType = f64;
+pub const STDIN_NAME: &str = "<stdin>";
 
+pub fn read_all(mut reader: impl Read, name: &str) -> Result<String, ParseError> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents).map_err(|err| ParseError::io(name, &err))?;
+    Ok(contents)
+}
 
-fn reconstruct_path(came_from: &HashMap<CoordType, CoordType>, current: CoordType) -> VecDeque<CoordType> {
-    let mut total_path: VecDeque<CoordType> = VecDeque::new();
-    total_path.push_back(current);
-    let mut current_mut = current;
-    while came_from.contains_key(&current_mut) {
-        current_mut = came_from[&current_mut];
-        total_path.push_front(current_mut);
+// Reads a whole input file, where "-" means stdin
+pub fn read_input(file_path: &str) -> Result<String, ParseError> {
+    if file_path == "-" {
+        read_all(io::stdin().lock(), STDIN_NAME)
+    } else {
+        fs::read_to_string(file_path).map_err(|err| ParseError::io(file_path, &err))
     }
-    return total_path;
 }
 
-pub fn a_star(board: &Array2D<ValueType>, start: CoordType, goal: CoordType) -> Option<VecDeque<CoordType>> {
-    let h = |coord: CoordType| {
-        let pair0 = (coord.0 as f64 - goal.0 as f64);
-        let pair1 = (coord.1 as f64 - goal.1 as f64);
-        (pair0*pair0 + pair1*pair1).sqrt()
-    };
+// How a path given to read_input should be referred to in errors
+pub fn input_name(file_path: &str) -> &str {
+    if file_path == "-" {
+        STDIN_NAME
+    } else {
+        file_path
+    }
+}
 
-    let mut open_set = HashSet::new();
-    open_set.insert(start);
-    let mut came_from : HashMap<CoordType, CoordType> = HashMap::new();
-
-    let mut g_score: HashMap<CoordType, CostType> = HashMap::new();
-    g_score.insert(start, 0f64);
-
-    let mut f_score: HashMap<CoordType, CostType> = HashMap::new();
-    f_score.insert(start, h(start) as f64);
-
-    while !open_set.is_empty() {
-        let current = {
-            let mut lowest_coord:  Option<CoordType> = None;
-            let mut lowest_cost: Option<CostType> = None;
-
-            for coord in open_set.iter() {
-                let cost = f_score.get(coord).unwrap_or(&f64::INFINITY);
-                match lowest_cost {
-                    None => {
-                        lowest_coord = Some(*coord);
-                        lowest_cost = Some(*cost);
-                    },
-                    Some(item) => {
-                        if *cost < item {
-                            lowest_coord = Some(*coord);
-                            lowest_cost = Some(*cost);
-                        }
-                    }
-                }
+pub fn numbered_lines(contents: &str) -> Vec<Line> {
+    contents.split('\n').enumerate().map(|(idx, text)| Line::new(idx + 1, text.trim_end_matches('\r'))).collect()
+}
+
+pub fn get_trimmed_lines(contents: &str) -> Vec<Line> {
+    numbered_lines(contents).iter().map(|line| line.trimmed()).filter(|line| !line.is_empty()).collect()
+}
+
+pub fn parse_digit_grid(contents: &str) -> Result<Array2D<u8>, ParseError> {
+    let lines = get_trimmed_lines(contents);
+    let rows: Vec<Vec<u8>> = lines.iter().map(|line| {
+        line.char_indices().map(|(idx, c)| {
+            match c.to_digit(10) {
+                Some(digit) => Ok(digit as u8),
+                None => Err(line.error_at(&line[idx..idx + c.len_utf8()], "a digit"))
             }
-            //println!("lowest is {:?} {:?}", lowest_coord, lowest_cost);
-            lowest_coord.unwrap()
+        }).collect()
+    }).collect::<Result<_, _>>()?;
+
+    grid_from_rows(&lines, &rows)
+}
+
+// Array2D::from_rows only reports that the rows are ragged, so find the line responsible
+fn check_rows<T>(lines: &[Line], rows: &[Vec<T>]) -> Result<(), ParseError> {
+    let width = match rows.first() {
+        Some(first) => first.len(),
+        None => return Err(ParseError::new(1, 1, "", "at least one row")),
+    };
+
+    for (line, row) in lines.iter().zip(rows) {
+        if row.len() != width {
+            return Err(line.error(&format!("a row of length {}", width)));
+        }
+    }
+    Ok(())
+}
+
+pub fn grid_from_rows<T: Clone>(lines: &[Line], rows: &[Vec<T>]) -> Result<Array2D<T>, ParseError> {
+    check_rows(lines, rows)?;
+    Array2D::from_rows(rows).map_err(|_| ParseError::new(1, 1, "", "rows of equal length"))
+}
+
+// (row, column), which can be negative
+pub type Coord = (i64, i64);
+
+pub const NEIGHBORS_4: [Coord; 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+pub const NEIGHBORS_8: [Coord; 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// A rectangle of cells which doesn't have to start at (0, 0). Reading outside of it gives the
+// fill value and setting outside of it grows the rectangle to fit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    mins: Coord,
+    num_rows: usize,
+    num_columns: usize,
+    fill: T,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(fill: T) -> Grid<T> {
+        Grid {
+            cells: Vec::new(),
+            mins: (0, 0),
+            num_rows: 0,
+            num_columns: 0,
+            fill,
+        }
+    }
+
+    // maxes is inclusive
+    pub fn filled_with(fill: T, mins: Coord, maxes: Coord) -> Grid<T> {
+        let num_rows = (maxes.0 + 1 - mins.0).max(0) as usize;
+        let num_columns = (maxes.1 + 1 - mins.1).max(0) as usize;
+        Grid {
+            cells: vec![fill.clone(); num_rows * num_columns],
+            mins,
+            num_rows,
+            num_columns,
+            fill,
+        }
+    }
+
+    // Starts at (0, 0). Rows shorter than the longest one are padded with fill.
+    pub fn from_rows(rows: &[Vec<T>], fill: T) -> Grid<T> {
+        let num_columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(rows.len() * num_columns);
+        for row in rows {
+            cells.extend(row.iter().cloned());
+            cells.extend((row.len()..num_columns).map(|_| fill.clone()));
+        }
+        Grid {
+            cells,
+            mins: (0, 0),
+            num_rows: rows.len(),
+            num_columns,
+            fill,
+        }
+    }
+
+    // One row per line, where cell turns each character into a value or None if it isn't allowed
+    pub fn parse(lines: &[Line], fill: T, cell: impl Fn(char) -> Option<T>, expected: &str) -> Result<Grid<T>, ParseError> {
+        let rows: Vec<Vec<T>> = lines.iter().map(|line| {
+            line.char_indices().map(|(idx, c)| {
+                cell(c).ok_or_else(|| line.error_at(&line[idx..idx + c.len_utf8()], expected))
+            }).collect()
+        }).collect::<Result<_, _>>()?;
+        check_rows(lines, &rows)?;
+        Ok(Grid::from_rows(&rows, fill))
+    }
+
+    pub fn mins(&self) -> Coord {
+        self.mins
+    }
+
+    // inclusive, so an empty grid has maxes below its mins
+    pub fn maxes(&self) -> Coord {
+        (self.mins.0 + self.num_rows as i64 - 1, self.mins.1 + self.num_columns as i64 - 1)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.num_columns
+    }
+
+    pub fn fill(&self) -> &T {
+        &self.fill
+    }
+
+    fn index_of(&self, coord: Coord) -> Option<usize> {
+        let row = coord.0 - self.mins.0;
+        let col = coord.1 - self.mins.1;
+        if row < 0 || col < 0 || row >= self.num_rows as i64 || col >= self.num_columns as i64 {
+            None
+        } else {
+            Some(row as usize * self.num_columns + col as usize)
+        }
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        self.index_of(coord).is_some()
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.index_of(coord).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.index_of(coord).map(|idx| &mut self.cells[idx])
+    }
+
+    pub fn get_or_fill(&self, coord: Coord) -> &T {
+        self.get(coord).unwrap_or(&self.fill)
+    }
+
+    pub fn set(&mut self, coord: Coord, value: T) {
+        self.grow_to_include(coord);
+        let idx = self.index_of(coord).unwrap();
+        self.cells[idx] = value;
+    }
+
+    pub fn grow_to_include(&mut self, coord: Coord) {
+        if self.contains(coord) {
+            return;
+        }
+
+        let (mins, maxes) = if self.cells.is_empty() {
+            (coord, coord)
+        } else {
+            let maxes = self.maxes();
+            ((self.mins.0.min(coord.0), self.mins.1.min(coord.1)), (maxes.0.max(coord.0), maxes.1.max(coord.1)))
         };
-        //println!("current {:?} {:?}", current, goal);
 
-        if current == goal {
-            return Some(reconstruct_path(&came_from, current));
+        // adding rows at the bottom doesn't move anything, which keeps growing upwards like day 17 cheap
+        if !self.cells.is_empty() && mins == self.mins && maxes.1 == self.maxes().1 {
+            self.num_rows = (maxes.0 + 1 - mins.0) as usize;
+            self.cells.resize(self.num_rows * self.num_columns, self.fill.clone());
+            return;
+        }
+
+        let mut grown = Grid::filled_with(self.fill.clone(), mins, maxes);
+        for (coord, value) in self.iter() {
+            let idx = grown.index_of(coord).unwrap();
+            grown.cells[idx] = value.clone();
+        }
+        *self = grown;
+    }
+
+    // row major
+    pub fn coords(&self) -> impl DoubleEndedIterator<Item = Coord> + ExactSizeIterator + Clone + '_ {
+        let mins = self.mins;
+        let num_columns = self.num_columns;
+        (0..self.cells.len()).map(move |idx| (mins.0 + (idx / num_columns) as i64, mins.1 + (idx % num_columns) as i64))
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Coord> {
+        self.iter().find(|(_coord, value)| predicate(value)).map(|(coord, _value)| coord)
+    }
+
+    // Only the neighbors inside the grid
+    pub fn neighbors4(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBORS_4.iter().map(move |offset| (coord.0 + offset.0, coord.1 + offset.1)).filter(|neighbor| self.contains(*neighbor))
+    }
+
+    pub fn neighbors8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBORS_8.iter().map(move |offset| (coord.0 + offset.0, coord.1 + offset.1)).filter(|neighbor| self.contains(*neighbor))
+    }
+
+    pub fn row(&self, row: i64) -> Option<&[T]> {
+        let idx = self.index_of((row, self.mins.1))?;
+        Some(&self.cells[idx..idx + self.num_columns])
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        let num_columns = self.num_columns;
+        (0..self.num_rows).map(move |row| &self.cells[row * num_columns..(row + 1) * num_columns])
+    }
+
+    pub fn column(&self, col: i64) -> impl DoubleEndedIterator<Item = &T> {
+        let start = self.index_of((self.mins.0, col));
+        let num_rows = if start.is_some() { self.num_rows } else { 0 };
+        let num_columns = self.num_columns;
+        (0..num_rows).map(move |row| &self.cells[start.unwrap() + row * num_columns])
+    }
+
+    pub fn map<U: Clone>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(&f).collect(),
+            mins: self.mins,
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            fill: f(&self.fill),
         }
+    }
 
-        open_set.remove(&current);
-        for (neighbor_row, neighbor_col) in [
-            (current.0 as i64 - 1, current.1 as i64),
-            (current.0 as i64 + 1, current.1 as i64),
-            (current.0 as i64, current.1 as i64 - 1),
-            (current.0 as i64, current.1 as i64 + 1)
-        ] {
-            if neighbor_row < 0 || neighbor_col < 0 || neighbor_row as usize >= board.column_len() || neighbor_col as usize >= board.row_len() {
-                continue;
+    // One line per row, drawing each cell with cell
+    pub fn render(&self, cell: impl Fn(Coord, &T) -> char) -> String {
+        let mut rendered = String::with_capacity(self.num_rows * (self.num_columns + 1));
+        for (coord, value) in self.iter() {
+            rendered.push(cell(coord, value));
+            if coord.1 == self.maxes().1 {
+                rendered.push('\n');
             }
+        }
+        rendered
+    }
+}
+
+impl<T: Clone> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord).unwrap_or_else(|| panic!("{:?} is outside the grid {:?} to {:?}", coord, self.mins, self.maxes()))
+    }
+}
+
+impl<T: Clone> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        let (mins, maxes) = (self.mins, self.maxes());
+        self.get_mut(coord).unwrap_or_else(|| panic!("{:?} is outside the grid {:?} to {:?}", coord, mins, maxes))
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|_coord, c| *c))
+    }
+}
+
+pub type Cost = u64;
+
+// Everything a search found, with nodes numbered in the order they were first reached
+struct Search<N> {
+    nodes: Vec<N>,
+    costs: Vec<Cost>,
+    came_from: Vec<Option<usize>>,
+}
+
+impl<N: Clone> Search<N> {
+    fn path(&self, idx: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[idx].clone()];
+        let mut current = idx;
+        while let Some(prev) = self.came_from[current] {
+            path.push(self.nodes[prev].clone());
+            current = prev;
+        }
+        path.reverse();
+        path
+    }
+}
+
+fn search<N, I>(start: N, mut is_goal: impl FnMut(&N) -> bool, mut neighbors: impl FnMut(&N) -> I, mut heuristic: impl FnMut(&N) -> Cost) -> (Search<N>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    let mut found = Search { nodes: vec![start.clone()], costs: vec![0], came_from: vec![None] };
+    let mut indices: HashMap<N, usize> = HashMap::new();
+    indices.insert(start.clone(), 0);
+
+    // ordered by estimated total cost, then by cost so far, then by node number
+    let mut open_set = BinaryHeap::new();
+    open_set.push(Reverse((heuristic(&start), 0, 0)));
+
+    while let Some(Reverse((_estimate, cost, current))) = open_set.pop() {
+        // a cheaper way here was found after this entry was queued
+        if cost > found.costs[current] {
+            continue;
+        }
+
+        let node = found.nodes[current].clone();
+        if is_goal(&node) {
+            return (found, Some(current));
+        }
 
-            let neighbor = (neighbor_row as usize, neighbor_col as usize);
-            //println!("current {:?} neighbor {:?} {} {}", current, neighbor, board.row_len(), board.column_len());
-            if board[current] + 1 < board[neighbor] {
-                continue;
+        for (neighbor, step_cost) in neighbors(&node) {
+            let tentative_cost = cost + step_cost;
+            let idx = match indices.get(&neighbor) {
+                Some(idx) => *idx,
+                None => {
+                    let idx = found.nodes.len();
+                    indices.insert(neighbor.clone(), idx);
+                    found.nodes.push(neighbor);
+                    found.costs.push(Cost::MAX);
+                    found.came_from.push(None);
+                    idx
+                }
+            };
+            if tentative_cost < found.costs[idx] {
+                found.costs[idx] = tentative_cost;
+                found.came_from[idx] = Some(current);
+                open_set.push(Reverse((tentative_cost + heuristic(&found.nodes[idx]), tentative_cost, idx)));
             }
+        }
+    }
+
+    (found, None)
+}
 
-            let tentative_g_score = g_score.get(&current).unwrap_or(&f64::INFINITY) + 1f64;
-            if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&f64::INFINITY) {
-                came_from.insert(neighbor, current);
-                g_score.insert(neighbor, tentative_g_score);
-                f_score.insert(neighbor, tentative_g_score + h(neighbor));
-                if !open_set.contains(&neighbor) {
-                    open_set.insert(neighbor);
+// The cheapest path from start to a node where is_goal is true, including both ends. neighbors gives
+// the nodes one step away along with the cost of the step, and heuristic must never overestimate
+// the cost left to a goal.
+pub fn a_star<N, I>(start: N, is_goal: impl FnMut(&N) -> bool, neighbors: impl FnMut(&N) -> I, heuristic: impl FnMut(&N) -> Cost) -> Option<(Cost, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    let (found, goal) = search(start, is_goal, neighbors, heuristic);
+    goal.map(|idx| (found.costs[idx], found.path(idx)))
+}
+
+// The cost of the cheapest path from start to every node it can reach
+pub fn dijkstra<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> HashMap<N, Cost>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    let (found, _goal) = search(start, |_| false, neighbors, |_| 0);
+    found.nodes.into_iter().zip(found.costs).collect()
+}
+
+// The cost of the cheapest path between every pair of nodes, or None where there isn't one. Nodes are numbered
+// from 0 and edges[node] gives the nodes one step away along with the cost of the step.
+pub fn floyd_warshall(edges: &[Vec<(usize, Cost)>]) -> Vec<Vec<Option<Cost>>> {
+    let mut costs: Vec<Vec<Option<Cost>>> = vec![vec![None; edges.len()]; edges.len()];
+    for (node, steps) in edges.iter().enumerate() {
+        costs[node][node] = Some(0);
+        for (next, cost) in steps.iter() {
+            costs[node][*next] = Some(costs[node][*next].map_or(*cost, |old| old.min(*cost)));
+        }
+    }
+
+    for via in 0..edges.len() {
+        let from_via = costs[via].clone();
+        for from_costs in costs.iter_mut() {
+            let to_via = match from_costs[via] {
+                Some(to_via) => to_via,
+                None => continue,
+            };
+            for (to, via_to) in from_via.iter().enumerate() {
+                if let Some(via_to) = via_to {
+                    if from_costs[to].is_none_or(|old| to_via + via_to < old) {
+                        from_costs[to] = Some(to_via + via_to);
+                    }
                 }
             }
         }
     }
+    costs
+}
+
+pub fn manhattan_distance(a: Coord, b: Coord) -> Cost {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+// One thread per core, or 1 if that can't be found out
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map(|jobs| jobs.get()).unwrap_or(1)
+}
+
+// Like items.iter().map(f).collect() but on up to jobs threads, each taking the next item when it's done with
+// one so a few slow items don't hold up the rest. The results stay in the same order as the items.
+pub fn parallel_map<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
 
-    None
-}
\ No newline at end of file
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs).map(|_| scope.spawn(|| {
+            let mut done = Vec::new();
+            loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                if idx >= items.len() {
+                    return done;
+                }
+                done.push((idx, f(&items[idx])));
+            }
+        })).collect();
+        for worker in workers {
+            // a panic in f carries on in this thread with the same message
+            for (idx, result) in worker.join().unwrap_or_else(|payload| panic::resume_unwind(payload)) {
+                results[idx] = Some(result);
+            }
+        }
+    });
+    results.into_iter().map(|result| result.unwrap()).collect()
+}
+
+#[test]
+fn test_line_errors() {
+    let line = Line::new(4, "  x=12, y=ab").trimmed();
+    assert_eq!(line.text, "x=12, y=ab");
+    assert_eq!(line.column, 3);
+    assert_eq!(line.parse_at::<i64>(&line[2..4], "x"), Ok(12));
+
+    let err = line.parse_at::<i64>(&line[8..], "an integer").unwrap_err();
+    assert_eq!((err.line, err.column, err.text.as_str()), (4, 11, "ab"));
+}
+
+#[test]
+fn test_grid() {
+    let lines = get_trimmed_lines("#.\n.#\n");
+    let mut grid = Grid::parse(&lines, '.', |c| if c == '#' || c == '.' { Some(c) } else { None }, "# or .").unwrap();
+    assert_eq!((grid.mins(), grid.maxes()), ((0, 0), (1, 1)));
+    assert_eq!(grid[(1, 1)], '#');
+    assert_eq!(grid.get((2, 0)), None);
+    assert_eq!(*grid.get_or_fill((2, 0)), '.');
+
+    grid.set((-1, 3), '#');
+    assert_eq!((grid.mins(), grid.maxes()), ((-1, 0), (1, 3)));
+    assert_eq!(grid.to_string(), "...#\n#...\n.#..\n");
+    assert_eq!(grid.row(0), Some(&['#', '.', '.', '.'][..]));
+    assert_eq!(grid.column(3).collect::<String>(), "#..");
+    assert_eq!(grid.rows().count(), 3);
+    assert_eq!(grid.position(|c| *c == '#'), Some((-1, 3)));
+
+    grid.set((3, 3), '#');
+    assert_eq!(grid.maxes(), (3, 3));
+    assert_eq!(grid[(-1, 3)], '#');
+    assert_eq!(grid[(3, 3)], '#');
+
+    assert_eq!(grid.neighbors4((-1, 0)).collect::<Vec<_>>(), vec![(0, 0), (-1, 1)]);
+    assert_eq!(grid.neighbors8((0, 0)).count(), 5);
+
+    let err = Grid::parse(&get_trimmed_lines("#.\n#x\n"), '.', |c| if c == '#' || c == '.' { Some(c) } else { None }, "# or .").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 2));
+    let err = Grid::parse(&get_trimmed_lines("#.\n#\n"), '.', Some, "anything").unwrap_err();
+    assert_eq!(err.line, 2);
+}
+
+#[test]
+fn test_search() {
+    // a weighted graph where the direct edge is the expensive way round
+    let edges = |node: &char| -> Vec<(char, Cost)> {
+        match node {
+            'a' => vec![('b', 1), ('d', 10)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 3)],
+            _ => vec![],
+        }
+    };
+    assert_eq!(a_star('a', |node| *node == 'd', edges, |_| 0), Some((6, vec!['a', 'b', 'c', 'd'])));
+    assert_eq!(a_star('d', |node| *node == 'a', edges, |_| 0), None);
+    assert_eq!(dijkstra('a', edges)[&'d'], 6);
+    let numbered: Vec<Vec<(usize, Cost)>> = "abcd".chars().map(|node| edges(&node).into_iter().map(|(next, cost)| ((next as u8 - b'a') as usize, cost)).collect()).collect();
+    let costs = floyd_warshall(&numbered);
+    assert_eq!(costs[0], vec![Some(0), Some(1), Some(3), Some(6)]);
+    assert_eq!(costs[3][0], None);
+
+    let grid = Grid::parse(&get_trimmed_lines("..#\n.##\n...\n"), '#', Some, "anything").unwrap();
+    let open = |coord: &Coord| grid.neighbors4(*coord).filter(|next| grid[*next] == '.').map(|next| (next, 1)).collect::<Vec<_>>();
+    let (cost, path) = a_star((0, 0), |coord| *coord == (2, 2), open, |coord| manhattan_distance(*coord, (2, 2))).unwrap();
+    assert_eq!(cost, 4);
+    assert_eq!(path, vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]);
+}
+
+#[test]
+fn test_parallel_map() {
+    let items: Vec<u64> = (0..100).collect();
+    let squares: Vec<u64> = items.iter().map(|n| n * n).collect();
+    assert_eq!(parallel_map(&items, 4, |n| n * n), squares);
+    assert_eq!(parallel_map(&items, 1, |n| n * n), squares);
+    assert_eq!(parallel_map(&[] as &[u64], 4, |n| n * n), Vec::<u64>::new());
+}
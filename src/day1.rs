@@ -1,29 +1,47 @@
-use std::fs;
+use crate::common::numbered_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-fn parse_calories(file_path: &str) -> Vec<Vec<u64>> {
-    let contents = fs::read_to_string(file_path).unwrap();
-
-    let lines = contents.split("\n").map(|line| line.trim());
+pub fn parse_calories(contents: &str) -> Result<Vec<Vec<u64>>, ParseError> {
+    let lines = numbered_lines(contents).iter().map(|line| line.trimmed()).collect::<Vec<_>>();
 
     let mut elfs: Vec<Vec<u64>> = Vec::new();
     let mut current_group: Vec<u64> = Vec::new();
     for line in lines {
-        if line.len() == 0 {
+        if line.is_empty() {
             elfs.push(current_group);
             current_group = Vec::new();
         } else {
-            current_group.push(line.parse().unwrap());
+            current_group.push(line.parse_at(&line, "a calorie count")?);
         }
     }
 
     elfs.push(current_group);
-    elfs
+    Ok(elfs)
 }
 
 
-pub fn part1(file_path: &str) -> u64 {
-    let elfs = parse_calories(file_path);
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    type Input = Vec<Vec<u64>>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse_calories(contents)
+    }
 
+    fn part1(elfs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(elfs).into())
+    }
+
+    fn part2(elfs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(elfs).into())
+    }
+}
+
+pub fn part1(elfs: &[Vec<u64>]) -> u64 {
     let sums: Vec<u64> = elfs.iter().map(|group| group.iter().sum()).collect();
     let max = *sums.iter().max().unwrap();
 
@@ -31,13 +49,34 @@ pub fn part1(file_path: &str) -> u64 {
 }
 
 
-pub fn part2(file_path: &str) -> u64 {
-    let elfs = parse_calories(file_path);
-
+pub fn part2(elfs: &[Vec<u64>]) -> u64 {
     let mut sums: Vec<u64> = elfs.iter().map(|group| group.iter().sum()).collect();
     sums.sort();
     let slice = &sums[sums.len() - 3..];
     let sum = slice.iter().sum::<u64>();
 
     sum
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "1000\n",
+        "2000\n",
+        "3000\n",
+        "\n",
+        "4000\n",
+        "\n",
+        "5000\n",
+        "6000\n",
+        "\n",
+        "7000\n",
+        "8000\n",
+        "9000\n",
+        "\n",
+        "10000\n",
+    );
+    let elfs = Day1::parse(contents).unwrap();
+    assert_eq!(part1(&elfs), 24000);
+    assert_eq!(part2(&elfs), 45000);
+}
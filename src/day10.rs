@@ -1,30 +1,57 @@
-use crate::common::get_trimmed_lines;
+use log::{debug, trace};
+use crate::common::{get_trimmed_lines, Line};
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-fn calc_signal_changes(lines: &Vec<String>) -> Vec<(i64, i64)> {
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Input = Vec<(i64, i64)>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+
+        calc_signal_changes(&lines)
+    }
+
+    fn part1(signal_changes: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(signal_changes).into())
+    }
+
+    fn part2(signal_changes: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(signal_changes).into())
+    }
+}
+
+pub fn calc_signal_changes(lines: &[Line]) -> Result<Vec<(i64, i64)>, ParseError> {
     let mut signal_changes: Vec<(i64, i64)> = Vec::new();
     let mut clock = 0;
     let mut x = 1;
     signal_changes.push((0, 1));
     for line in lines {
-        let mut pieces = line.split(" ");
-        let command = pieces.next().unwrap();
+        let mut pieces = line.split(' ');
+        let command = pieces.next().unwrap_or_default();
 
         if command == "noop" {
             clock += 1;
         } else if command == "addx" {
             clock += 2;
-            let num_str = pieces.next().unwrap();
-            let num: i64 = num_str.parse().unwrap();
+            let num: i64 = match pieces.next() {
+                Some(num_str) => line.parse_at(num_str, "an integer to add")?,
+                None => return Err(line.error("an instruction like \"addx 3\""))
+            };
             x += num;
             signal_changes.push((clock, x));
         } else {
-            panic!("Unknown command {}", command);
+            return Err(line.error_at(command, "an instruction, either \"noop\" or \"addx\""));
         }
     }
-    signal_changes
+    Ok(signal_changes)
 }
 
-fn lookup_signal(key: i64, signal_changes: &Vec<(i64, i64)>) -> i64 {
+pub fn lookup_signal(key: i64, signal_changes: &[(i64, i64)]) -> i64 {
     let lookup = key - 1;
     let result = signal_changes.binary_search_by_key(&lookup, |&(_clock, _strength)| _clock);
     match result {
@@ -38,30 +65,22 @@ fn lookup_signal(key: i64, signal_changes: &Vec<(i64, i64)>) -> i64 {
     }
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let lines = get_trimmed_lines(file_path);
-
-    let signal_changes = calc_signal_changes(&lines);
+pub fn part1(signal_changes: &Vec<(i64, i64)>) -> i64 {
+    trace!("signal_changes {:?}", signal_changes);
+    let signals = [20, 60, 100, 140, 180, 220].map(|clock| (clock, lookup_signal(clock, signal_changes)));
 
-    println!("signal_changes {:?}", signal_changes);
-    let signals = [20, 60, 100, 140, 180, 220].map(|clock| (clock, lookup_signal(clock, &signal_changes)));
-
-    println!("signals {:?}", signals);
+    debug!("signals {:?}", signals);
     signals.iter().map(|&(clock, strength) | clock * strength).sum()
 }
 
-pub fn part2(file_path: &str) -> String {
-    let lines = get_trimmed_lines(file_path);
-
-    let signal_changes = calc_signal_changes(&lines);
-
+pub fn part2(signal_changes: &[(i64, i64)]) -> String {
     let mut pixels: Vec<char> = Vec::new();
     pixels.push('\n');
     for clock_y in 0..6 {
         for clock_x in 0..40 {
             let clock = 40*clock_y + clock_x;
-            let signal = lookup_signal(clock + 1, &signal_changes);
-            println!("signal {:?} {:?}", signal, clock_x);
+            let signal = lookup_signal(clock + 1, signal_changes);
+            trace!("signal {:?} {:?}", signal, clock_x);
             let has_pixel = signal - 1 <= clock_x && signal + 1 >= clock_x;
 
             if has_pixel {
@@ -74,4 +93,167 @@ pub fn part2(file_path: &str) -> String {
     }
 
     pixels.iter().collect()
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "addx 15\n",
+        "addx -11\n",
+        "addx 6\n",
+        "addx -3\n",
+        "addx 5\n",
+        "addx -1\n",
+        "addx -8\n",
+        "addx 13\n",
+        "addx 4\n",
+        "noop\n",
+        "addx -1\n",
+        "addx 5\n",
+        "addx -1\n",
+        "addx 5\n",
+        "addx -1\n",
+        "addx 5\n",
+        "addx -1\n",
+        "addx 5\n",
+        "addx -1\n",
+        "addx -35\n",
+        "addx 1\n",
+        "addx 24\n",
+        "addx -19\n",
+        "addx 1\n",
+        "addx 16\n",
+        "addx -11\n",
+        "noop\n",
+        "noop\n",
+        "addx 21\n",
+        "addx -15\n",
+        "noop\n",
+        "noop\n",
+        "addx -3\n",
+        "addx 9\n",
+        "addx 1\n",
+        "addx -3\n",
+        "addx 8\n",
+        "addx 1\n",
+        "addx 5\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx -36\n",
+        "noop\n",
+        "addx 1\n",
+        "addx 7\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx 2\n",
+        "addx 6\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx 1\n",
+        "noop\n",
+        "noop\n",
+        "addx 7\n",
+        "addx 1\n",
+        "noop\n",
+        "addx -13\n",
+        "addx 13\n",
+        "addx 7\n",
+        "noop\n",
+        "addx 1\n",
+        "addx -33\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx 2\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx 8\n",
+        "noop\n",
+        "addx -1\n",
+        "addx 2\n",
+        "addx 1\n",
+        "noop\n",
+        "addx 17\n",
+        "addx -9\n",
+        "addx 1\n",
+        "addx 1\n",
+        "addx -3\n",
+        "addx 11\n",
+        "noop\n",
+        "noop\n",
+        "addx 1\n",
+        "noop\n",
+        "addx 1\n",
+        "noop\n",
+        "noop\n",
+        "addx -13\n",
+        "addx -19\n",
+        "addx 1\n",
+        "addx 3\n",
+        "addx 26\n",
+        "addx -30\n",
+        "addx 12\n",
+        "addx -1\n",
+        "addx 3\n",
+        "addx 1\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx -9\n",
+        "addx 18\n",
+        "addx 1\n",
+        "addx 2\n",
+        "noop\n",
+        "noop\n",
+        "addx 9\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+        "addx -1\n",
+        "addx 2\n",
+        "addx -37\n",
+        "addx 1\n",
+        "addx 3\n",
+        "noop\n",
+        "addx 15\n",
+        "addx -21\n",
+        "addx 22\n",
+        "addx -6\n",
+        "addx 1\n",
+        "noop\n",
+        "addx 2\n",
+        "addx 1\n",
+        "noop\n",
+        "addx -10\n",
+        "noop\n",
+        "noop\n",
+        "addx 20\n",
+        "addx 1\n",
+        "addx 2\n",
+        "addx 2\n",
+        "addx -6\n",
+        "addx -11\n",
+        "noop\n",
+        "noop\n",
+        "noop\n",
+    );
+    let signal_changes = Day10::parse(contents).unwrap();
+    assert_eq!(part1(&signal_changes), 13140);
+    assert_eq!(part2(&signal_changes), concat!(
+        "\n",
+        "##..##..##..##..##..##..##..##..##..##..\n",
+        "###...###...###...###...###...###...###.\n",
+        "####....####....####....####....####....\n",
+        "#####.....#####.....#####.....#####.....\n",
+        "######......######......######......####\n",
+        "#######.......#######.......#######.....\n",
+    ));
+}
@@ -1,9 +1,13 @@
 use std::collections::VecDeque;
 use std::fmt;
-use crate::common::get_trimmed_lines;
+use crate::common::{get_trimmed_lines, Line};
+use crate::error::ParseError;
+use crate::params::{self, parse_value, ParamError};
 use regex::Regex;
 use lazy_static::lazy_static;
+use log::trace;
 use fmt::Debug;
+use crate::solution::{Answer, Solution};
 
 lazy_static! {
     static ref MONKEY_RE: Regex = Regex::new(r"Monkey (\d+):").unwrap();
@@ -13,156 +17,152 @@ lazy_static! {
     static ref IF_RE: Regex = Regex::new(r"If (true|false): throw to monkey (\d+)").unwrap();
 }
 
-#[derive(Debug)]
-enum Operation {
+#[derive(Debug, Clone)]
+pub enum Operation {
     Add,
     Multiply,
 }
 
-#[derive(Debug)]
-struct Monkey {
-    number: usize,
-    items: VecDeque<usize>,
-    operation: Operation,
-    operand: Option<usize>,
-    test: usize,
-    when_true: usize,
-    when_false: usize,
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    pub number: usize,
+    pub items: VecDeque<usize>,
+    pub operation: Operation,
+    pub operand: Option<usize>,
+    pub test: usize,
+    pub when_true: usize,
+    pub when_false: usize,
 }
 
-fn make_monkey_lists(lines: &Vec<String>) -> Vec<Monkey> {
+// The notes read so far for the monkey currently being parsed
+#[derive(Default)]
+struct MonkeyNotes {
+    number: Option<usize>,
+    items: Option<VecDeque<usize>>,
+    operation: Option<Operation>,
+    operand: Option<Option<usize>>,
+    test: Option<usize>,
+    when_true: Option<usize>,
+    when_false: Option<usize>,
+}
+
+impl MonkeyNotes {
+    // error is for the position where the notes ended
+    fn build(self, error: impl Fn(&str) -> ParseError) -> Result<Monkey, ParseError> {
+        Ok(Monkey {
+            number: self.number.ok_or_else(|| error("a \"Monkey 0:\" line"))?,
+            items: self.items.ok_or_else(|| error("a \"Starting items:\" line"))?,
+            operation: self.operation.ok_or_else(|| error("an \"Operation:\" line"))?,
+            operand: self.operand.ok_or_else(|| error("an \"Operation:\" line"))?,
+            test: self.test.ok_or_else(|| error("a \"Test:\" line"))?,
+            when_true: self.when_true.ok_or_else(|| error("an \"If true:\" line"))?,
+            when_false: self.when_false.ok_or_else(|| error("an \"If false:\" line"))?,
+        })
+    }
+}
+
+pub fn make_monkey_lists(lines: &[Line]) -> Result<Vec<Monkey>, ParseError> {
     let mut monkeys: Vec<Monkey> = Vec::new();
 
-    let mut monkey_num: Option<usize> = None;
-    let mut items: Option<VecDeque<usize>> = None;
-    let mut operation: Option<Operation> = None;
-    let mut operand: Option<Option<usize>> = None;
-    let mut test:  Option<usize> = None;
-    let mut when_true: Option<usize> = None;
-    let mut when_false: Option<usize> = None;
+    let mut notes = MonkeyNotes::default();
+    // monkeys thrown to, checked once we know how many monkeys there are
+    let mut targets: Vec<(usize, &Line, &str)> = Vec::new();
 
     for line in lines {
-        match MONKEY_RE.captures(line) {
-            Some(cap) => {
-                let _monkey_num: usize = cap.get(1).unwrap().as_str().parse().unwrap();
-
-                if _monkey_num != 0 {
-                    monkeys.push(Monkey {
-                        number: monkey_num.unwrap(),
-                        items: items.unwrap(),
-                        operation: operation.unwrap(),
-                        operand: operand.unwrap(),
-                        test: test.unwrap(),
-                        when_true: when_true.unwrap(),
-                        when_false: when_false.unwrap(),
-                    });
-
-                    monkey_num = None;
-                    items = None;
-                    operation = None;
-                    operand = None;
-                    test = None;
-                    when_true = None;
-                    when_false = None;
-                }
-
-                if _monkey_num != monkeys.len() {
-                    panic!("Unknown monkey {}", _monkey_num);
-                }
-
-                monkey_num = Some(_monkey_num);
-
-                continue;
-            },
-            None => { }
+        if let Some(cap) = MONKEY_RE.captures(line) {
+            let num_str = cap.get(1).unwrap().as_str();
+            let _monkey_num: usize = line.parse_at(num_str, "a monkey number")?;
+
+            if _monkey_num != 0 {
+                monkeys.push(std::mem::take(&mut notes).build(|expected| line.error(expected))?);
+            }
+
+            if _monkey_num != monkeys.len() {
+                return Err(line.error_at(num_str, &format!("monkey number {}", monkeys.len())));
+            }
+
+            notes.number = Some(_monkey_num);
+
+            continue;
         };
 
-        match STARTING_RE.captures(line) {
-            Some(cap) => {
-                let _items: VecDeque<usize> = cap.get(1).unwrap().as_str().split(",").map(|piece| piece.trim().parse::<usize>().unwrap()).collect();
-                items = Some(_items);
-                continue;
-            },
-            None => {}
+        if let Some(cap) = STARTING_RE.captures(line) {
+            let _items: VecDeque<usize> = cap.get(1).unwrap().as_str().split(',').map(|piece| {
+                line.parse_at::<usize>(piece.trim(), "a worry level")
+            }).collect::<Result<_, _>>()?;
+            notes.items = Some(_items);
+            continue;
         }
 
-        match OPERATION_RE.captures(line) {
-            Some(cap) => {
-                let _operation = cap.get(1).unwrap().as_str();
-                operation = Some(match _operation {
-                    "*" => Operation::Multiply,
-                    "+" => Operation::Add,
-                    _ => panic!("Unknown operation")
-                });
-                let _operand = cap.get(2).unwrap().as_str();
-                if _operand == "old" {
-                    operand = Some(None)
-                } else {
-                    operand = Some(Some(_operand.parse().unwrap()));
-                }
-                continue;
-            },
-            None => {}
+        if let Some(cap) = OPERATION_RE.captures(line) {
+            let _operation = cap.get(1).unwrap().as_str();
+            notes.operation = Some(match _operation {
+                "*" => Operation::Multiply,
+                _ => Operation::Add,
+            });
+            let _operand = cap.get(2).unwrap().as_str();
+            if _operand == "old" {
+                notes.operand = Some(None)
+            } else {
+                notes.operand = Some(Some(line.parse_at(_operand, "an operand")?));
+            }
+            continue;
         }
 
-        match TEST_RE.captures(line) {
-            Some(cap) => {
-                let _divisible: usize = cap.get(1).unwrap().as_str().parse().unwrap();
-                test = Some(_divisible);
-                continue;
-            },
-            None => {}
+        if let Some(cap) = TEST_RE.captures(line) {
+            let divisor_str = cap.get(1).unwrap().as_str();
+            let _divisible: usize = line.parse_at(divisor_str, "a divisor")?;
+            if _divisible == 0 {
+                return Err(line.error_at(divisor_str, "a divisor above 0"));
+            }
+            notes.test = Some(_divisible);
+            continue;
         }
 
-        match IF_RE.captures(line) {
-            Some(cap) => {
-                let true_or_false = cap.get(1).unwrap().as_str();
-                let other_monkey = cap.get(2).unwrap().as_str().parse().unwrap();
-                match true_or_false {
-                    "true" => {
-                        when_true = Some(other_monkey);
-                    },
-                    "false" => {
-                        when_false = Some(other_monkey);
-                    },
-                    _ => {
-                        panic!("unexpected true_or_false");
-                    }
-                }
-                continue;
-            },
-            None => {}
+        if let Some(cap) = IF_RE.captures(line) {
+            let true_or_false = cap.get(1).unwrap().as_str();
+            let num_str = cap.get(2).unwrap().as_str();
+            let other_monkey = line.parse_at(num_str, "a monkey number")?;
+            targets.push((other_monkey, line, num_str));
+            if true_or_false == "true" {
+                notes.when_true = Some(other_monkey);
+            } else {
+                notes.when_false = Some(other_monkey);
+            }
+            continue;
         }
 
-        panic!("Unexpected line");
+        return Err(line.error("a line of monkey notes"));
     }
 
-    monkeys.push(Monkey {
-        number: monkey_num.unwrap(),
-        items: items.unwrap(),
-        operation: operation.unwrap(),
-        operand: operand.unwrap(),
-        test: test.unwrap(),
-        when_true: when_true.unwrap(),
-        when_false: when_false.unwrap(),
-    });
-
-    monkeys
+    let end = lines.last().map(|line| line.number + 1).unwrap_or(1);
+    monkeys.push(notes.build(|expected| ParseError::new(end, 1, "", expected))?);
+    // monkey business multiplies the two busiest monkeys
+    if monkeys.len() < 2 {
+        return Err(ParseError::new(end, 1, "", "notes for monkey 1"));
+    }
+
+    for (other_monkey, line, num_str) in targets {
+        if other_monkey >= monkeys.len() {
+            return Err(line.error_at(num_str, &format!("a monkey number below {}", monkeys.len())));
+        }
+    }
+
+    Ok(monkeys)
 }
 
-fn calc_monkey_business(file_path: &str, div_3: bool, num_rounds: usize) -> usize {
-    let lines = get_trimmed_lines(file_path);
-    let mut monkeys = make_monkey_lists(&lines);
+pub fn calc_monkey_business(monkeys: &[Monkey], div_3: bool, num_rounds: usize) -> usize {
+    let mut monkeys = monkeys.to_vec();
 
     let mut divisible = 1;
-    for monkey_idx in 0..monkeys.len() {
-        divisible *= monkeys[monkey_idx].test;
+    for monkey in monkeys.iter() {
+        divisible *= monkey.test;
     }
 
     let mut activity: Vec<usize> = (0..monkeys.len()).map(|_| 0).collect();
 
     for round in 0..num_rounds {
-        println!("{}", round);
+        trace!("round {}", round);
         for monkey_idx in 0..monkeys.len() {
 
             while !monkeys[monkey_idx].items.is_empty() {
@@ -201,14 +201,92 @@ fn calc_monkey_business(file_path: &str, div_3: bool, num_rounds: usize) -> usiz
     }
 
     activity.sort();
-    return activity[activity.len() - 1] * activity[activity.len() - 2];
+    activity[activity.len() - 1] * activity[activity.len() - 2]
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub part1_rounds: usize,
+    pub part2_rounds: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            part1_rounds: 20,
+            part2_rounds: 10000,
+        }
+    }
+}
 
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["part1_rounds", "part2_rounds"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "part1_rounds" => self.part1_rounds = parse_value(key, value, "a number of rounds")?,
+            "part2_rounds" => self.part2_rounds = parse_value(key, value, "a number of rounds")?,
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
+        }
+        Ok(())
+    }
 }
 
-pub fn part1(file_path: &str) -> usize {
-    calc_monkey_business(file_path, true, 20)
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    type Input = Vec<Monkey>;
+    type Config = Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+        make_monkey_lists(&lines)
+    }
+
+    fn part1(monkeys: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(calc_monkey_business(monkeys, true, config.part1_rounds).into())
+    }
+
+    fn part2(monkeys: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(calc_monkey_business(monkeys, false, config.part2_rounds).into())
+    }
 }
 
-pub fn part2(file_path: &str) -> usize {
-    calc_monkey_business(file_path, false, 10000)
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "Monkey 0:\n",
+        "  Starting items: 79, 98\n",
+        "  Operation: new = old * 19\n",
+        "  Test: divisible by 23\n",
+        "    If true: throw to monkey 2\n",
+        "    If false: throw to monkey 3\n",
+        "\n",
+        "Monkey 1:\n",
+        "  Starting items: 54, 65, 75, 74\n",
+        "  Operation: new = old + 6\n",
+        "  Test: divisible by 19\n",
+        "    If true: throw to monkey 2\n",
+        "    If false: throw to monkey 0\n",
+        "\n",
+        "Monkey 2:\n",
+        "  Starting items: 79, 60, 97\n",
+        "  Operation: new = old * old\n",
+        "  Test: divisible by 13\n",
+        "    If true: throw to monkey 1\n",
+        "    If false: throw to monkey 3\n",
+        "\n",
+        "Monkey 3:\n",
+        "  Starting items: 74\n",
+        "  Operation: new = old + 3\n",
+        "  Test: divisible by 17\n",
+        "    If true: throw to monkey 0\n",
+        "    If false: throw to monkey 1\n",
+    );
+    let monkeys = Day11::parse(contents).unwrap();
+    let config = Config::default();
+    assert_eq!(calc_monkey_business(&monkeys, true, config.part1_rounds), 10605);
+    assert_eq!(calc_monkey_business(&monkeys, false, config.part2_rounds), 2713310158);
 }
@@ -1,110 +1,228 @@
-use std::collections::HashSet;
-use array2d::Array2D;
-use crate::common::{ get_trimmed_lines, a_star };
-
-fn make_board(lines: &Vec<String>) -> Array2D<char> {
-    let rows: Vec<Vec<char>> = lines.iter().map(|line| {
-        line.chars().into_iter().collect()
-    }).collect();
-    Array2D::from_rows(
-        &rows
-    ).unwrap()
-}
-
-fn find_coord(board: &Array2D<char>, item: char) -> (usize, usize) {
-    for coord in board.indices_row_major() {
-        if board[coord] == item {
-            return coord
+use std::collections::HashMap;
+use crate::common::{ get_trimmed_lines, a_star, manhattan_distance, Coord, Cost, Grid, Line };
+use crate::error::ParseError;
+use crate::params::{self, ParamError};
+use crate::solution::{Answer, Solution};
+
+pub fn make_board(lines: &[Line]) -> Result<Grid<char>, ParseError> {
+    let board = Grid::parse(lines, 'a', |c| if matches!(c, 'a'..='z' | 'S' | 'E') { Some(c) } else { None }, "an elevation a-z, S or E")?;
+
+    for item in ['S', 'E'] {
+        let count = board.values().filter(|c| **c == item).count();
+        if count != 1 {
+            let end = lines.last().map(|line| line.number + 1).unwrap_or(1);
+            return Err(ParseError::new(end, 1, "", &format!("exactly one {} in the heightmap, found {}", item, count)));
         }
     }
 
-    panic!("Unexpected");
+    Ok(board)
+}
+
+pub fn find_coord(board: &Grid<char>, item: char) -> Coord {
+    board.position(|c| *c == item).expect("Unexpected")
 }
 
 fn _to_score(current: char) -> u8 {
     match current {
         'S' => 1,
         'a'..='z' => {
-            (current as u8) - ('a' as u8) + 1
+            (current as u8) - b'a' + 1
         },
         'E' => 26,
         _ => panic!("unexpected character")
     }
 }
 
-fn find_solution(current: (usize, usize), end: (usize, usize), board: &Array2D<char>, cost: usize, prev_path: &HashSet<(usize, usize)>) -> Option<usize> {
-    //println!("current {:?} {:?}", current, prev_path);
-    if current == end {
-        return Some(cost);
-    }
+// Where you can go in one step, which is anywhere at most one higher
+fn climb(board_scores: &Grid<u8>, current: Coord) -> impl Iterator<Item = (Coord, Cost)> + '_ {
+    board_scores.neighbors4(current).filter(move |neighbor| board_scores[current] + 1 >= board_scores[*neighbor]).map(|neighbor| (neighbor, 1))
+}
+
+// The reverse of climb, where you could have come from in one step
+fn descend(board_scores: &Grid<u8>, current: Coord) -> impl Iterator<Item = (Coord, Cost)> + '_ {
+    board_scores.neighbors4(current).filter(move |neighbor| board_scores[*neighbor] + 1 >= board_scores[current]).map(|neighbor| (neighbor, 1))
+}
 
-    let mut results = Vec::new();
-    for (neighbor_col, neighbor_row) in [
-        (current.0 as i64 - 1, current.1 as i64),
-        (current.0 as i64 + 1, current.1 as i64),
-        (current.0 as i64, current.1 as i64 - 1),
-        (current.0 as i64, current.1 as i64 + 1),
-    ] {
-        if neighbor_row < 0 || neighbor_col < 0 || neighbor_row >= board.row_len() as i64 || neighbor_col >= board.column_len() as i64 {
-            continue;
+fn shortest_path(board_scores: &Grid<u8>, start: Coord, end: Coord) -> Option<(Cost, Vec<Coord>)> {
+    a_star(start, |coord| *coord == end, |coord| climb(board_scores, *coord), |coord| manhattan_distance(*coord, end))
+}
+
+// Walks backwards from the end until it reaches the lowest elevation, which finds the best
+// starting square without searching from each one. The path is returned from start to end.
+fn shortest_path_from_lowest(board_scores: &Grid<u8>, end: Coord) -> Option<(Cost, Vec<Coord>)> {
+    let (steps, mut path) = a_star(end, |coord| board_scores[*coord] == 1, |coord| descend(board_scores, *coord), |_coord| 0)?;
+    path.reverse();
+    Some((steps, path))
+}
+
+// Each square of the path is drawn with the direction of the next step, like the puzzle text
+fn arrows(path: &[Coord]) -> HashMap<Coord, char> {
+    path.windows(2).map(|step| {
+        let arrow = match (step[1].0 - step[0].0, step[1].1 - step[0].1) {
+            (-1, 0) => '^',
+            (1, 0) => 'v',
+            (0, -1) => '<',
+            (0, 1) => '>',
+            _ => panic!("Unexpected step from {:?} to {:?}", step[0], step[1])
+        };
+        (step[0], arrow)
+    }).collect()
+}
+
+pub fn render_path(board: &Grid<char>, path: &[Coord]) -> String {
+    let arrows = arrows(path);
+    board.render(|coord, c| {
+        match arrows.get(&coord) {
+            Some(arrow) => *arrow,
+            None if *c == 'E' => 'E',
+            None => '.',
         }
+    })
+}
 
-        let neighbor_coord = (neighbor_col as usize, neighbor_row as usize);
-        if prev_path.contains(&neighbor_coord) {
-            continue;
+// Shades every square by elevation using the greys of the 256 color palette, with the path in red on top
+pub fn render_path_color(board: &Grid<char>, path: &[Coord]) -> String {
+    let arrows = arrows(path);
+    let mut rendered = String::new();
+    for (coord, c) in board.iter() {
+        let score = _to_score(*c) as u32;
+        let background = 232 + (score - 1) * 23 / 25;
+        let (foreground, c) = match arrows.get(&coord) {
+            Some(arrow) => ("1;91", *arrow),
+            None if score <= 13 => ("37", *c),
+            None => ("30", *c),
+        };
+        rendered.push_str(&format!("\x1b[0;{};48;5;{}m{}", foreground, background, c));
+        if coord.1 == board.maxes().1 {
+            rendered.push_str("\x1b[0m\n");
         }
+    }
+    rendered
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Render {
+    Off,
+    Text,
+    Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    // draw the chosen path before the answer, when running one day
+    pub render: Render,
+}
 
-        let mut new_path: HashSet<(usize, usize)> = prev_path.iter().map(|p| *p).collect();
-        new_path.insert(current);
-
-        let current_char = board[current];
-        let neighbor_char = board[neighbor_coord];
-
-        //println!("  neighbor {:?} {} {} {}", neighbor_coord, neighbor_char, _to_score(current_char), _to_score(neighbor_char));
-        if _to_score(current_char) + 1 >= _to_score(neighbor_char) {
-            let result = find_solution(neighbor_coord, end, &board, cost + 1, &new_path);
-            match result {
-                Some(_result) => {
-                    results.push(_result);
-                },
-                None => {}
-            }
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            render: Render::Off,
         }
     }
+}
+
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["render"];
 
-    return results.iter().min().map(|s| *s);
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, value) {
+            ("render", "off") => self.render = Render::Off,
+            ("render", "text") => self.render = Render::Text,
+            ("render", "color") => self.render = Render::Color,
+            ("render", _) => return Err(ParamError::Invalid { key: key.to_string(), value: value.to_string(), expected: "off, text or color" }),
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
+        }
+        Ok(())
+    }
+}
+
+// The steps along with a drawing of the path for the render param
+fn rendered(board: &Grid<char>, (steps, path): (Cost, Vec<Coord>), config: &Config) -> Answer {
+    let answer = Answer::from(steps as usize);
+    match config.render {
+        Render::Off => answer,
+        Render::Text => answer.explained(render_path(board, &path)),
+        Render::Color => answer.explained(render_path_color(board, &path)),
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Input = Grid<char>;
+    type Config = Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+        make_board(&lines)
+    }
+
+    fn part1(board: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(rendered(board, climb_from_start(board)?, config))
+    }
+
+    fn part2(board: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(rendered(board, climb_from_lowest(board)?, config))
+    }
 }
 
-pub fn part1(file_path: &str) -> usize {
-    let lines = get_trimmed_lines(file_path);
+fn climb_from_start(board: &Grid<char>) -> Result<(Cost, Vec<Coord>), ParseError> {
+    let board_scores = board.map(|c| _to_score(*c));
+
+    let start = find_coord(board, 'S');
+    let end = find_coord(board, 'E');
 
-    let board: Array2D<char> = make_board(&lines);
-    let board_scores: Array2D<u8> = Array2D::from_iter_row_major(
-        board.elements_row_major_iter().map(|c| _to_score(*c)),
-        board.num_rows(),
-        board.num_columns()
-    ).unwrap();
+    shortest_path(&board_scores, start, end).ok_or_else(|| ParseError::new(0, 0, "", "a way to climb from S to E"))
+}
 
-    let start = find_coord(&board, 'S');
-    let end = find_coord(&board, 'E');
+fn climb_from_lowest(board: &Grid<char>) -> Result<(Cost, Vec<Coord>), ParseError> {
+    let board_scores = board.map(|c| _to_score(*c));
 
-    let path = a_star(&board_scores, start, end).unwrap();
-    path.len() - 1
+    let end = find_coord(board, 'E');
+    shortest_path_from_lowest(&board_scores, end).ok_or_else(|| ParseError::new(0, 0, "", "a way to climb from elevation a to E"))
 }
 
-pub fn part2(file_path: &str) -> usize {
-    let lines = get_trimmed_lines(file_path);
-    let board: Array2D<char> = make_board(&lines);
-    let board_scores: Array2D<u8> = Array2D::from_iter_row_major(
-        board.elements_row_major_iter().map(|c| _to_score(*c)),
-        board.num_rows(),
-        board.num_columns()
-    ).unwrap();
+pub fn part1(board: &Grid<char>) -> Result<usize, ParseError> {
+    Ok(climb_from_start(board)?.0 as usize)
+}
 
-    let end = find_coord(&board, 'E');
-    board_scores.enumerate_row_major().filter(|(coord, value)| {
-        **value == 1
-    }).filter_map(|(coord, value)| {
-        a_star(&board_scores, coord, end)
-    }).map(|path| path.len()).min().unwrap() - 1
-}
\ No newline at end of file
+pub fn part2(board: &Grid<char>) -> Result<usize, ParseError> {
+    Ok(climb_from_lowest(board)?.0 as usize)
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "Sabqponm\n",
+        "abcryxxl\n",
+        "accszExk\n",
+        "acctuvwj\n",
+        "abdefghi\n",
+    );
+    let board = Day12::parse(contents).unwrap();
+    assert_eq!(part1(&board), Ok(31));
+    assert_eq!(part2(&board), Ok(29));
+
+    // S is as low as a, so it can't climb straight up to E, or up to z on the way to E
+    let cliff = Day12::parse("SE\n").unwrap();
+    assert_eq!(part1(&cliff).unwrap_err().to_string(), "expected a way to climb from S to E, found end of input");
+    assert_eq!(part2(&Day12::parse("EzS\n").unwrap()).unwrap_err().to_string(), "expected a way to climb from elevation a to E, found end of input");
+
+    let (steps, path) = shortest_path_from_lowest(&board.map(|c| _to_score(*c)), find_coord(&board, 'E')).unwrap();
+    assert_eq!(path.len(), steps as usize + 1);
+    assert_eq!(board[path[0]], 'a');
+    assert_eq!(board[*path.last().unwrap()], 'E');
+
+    let board_scores = board.map(|c| _to_score(*c));
+    let (_steps, path) = shortest_path(&board_scores, find_coord(&board, 'S'), find_coord(&board, 'E')).unwrap();
+    assert_eq!(render_path(&board, &path), concat!(
+        "v..v<<<<\n",
+        ">v.vv<<^\n",
+        ".>vv>E^^\n",
+        "..v>>>^^\n",
+        "..>>>>>^\n",
+    ));
+    assert!(render_path_color(&board, &path).starts_with("\x1b[0;1;91;48;5;232mv\x1b[0;37;48;5;232ma"));
+    assert_eq!(Day12::part1(&board, &Config { render: Render::Text }).unwrap().explanation(), Some(render_path(&board, &path).as_str()));
+}
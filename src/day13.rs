@@ -1,8 +1,10 @@
 use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
 use serde_json::{ Value, json };
 use std::cmp::{ Ordering, min };
+use crate::solution::{Answer, Solution};
 
-fn in_right_order(value1: &Value, value2: &Value) -> Ordering {
+pub fn in_right_order(value1: &Value, value2: &Value) -> Ordering {
     match value1 {
         Value::Array(_value1) => {
             match value2 {
@@ -24,7 +26,7 @@ fn in_right_order(value1: &Value, value2: &Value) -> Ordering {
                 Value::Number(_value2) => {
                     let number2 = _value2.as_i64().unwrap();
                     let value2 = json!(number2);
-                    in_right_order(&value1, &Value::Array(vec![value2]))
+                    in_right_order(value1, &Value::Array(vec![value2]))
                 },
                 _ => panic!("Unknown object")
             }
@@ -34,7 +36,7 @@ fn in_right_order(value1: &Value, value2: &Value) -> Ordering {
             match value2 {
                 Value::Array(_value2) => {
                     let value1 = json!(number1);
-                    in_right_order(&Value::Array(vec![value1]), &value2)
+                    in_right_order(&Value::Array(vec![value1]), value2)
                 },
                 Value::Number(_value2) => {
                     let number2 = _value2.as_i64().unwrap();
@@ -47,16 +49,57 @@ fn in_right_order(value1: &Value, value2: &Value) -> Ordering {
     }
 }
 
-pub fn part1(file_path: &str) -> u64 {
-    let lines = get_trimmed_lines(file_path);
+fn is_packet(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => items.iter().all(is_packet),
+        Value::Number(number) => number.is_i64(),
+        _ => false
+    }
+}
 
-    let mut sum: u64 = 0;
+pub struct Day13;
 
-    for idx in (0..lines.len()).step_by(2) {
-        let value1 = serde_json::from_str(lines[idx].as_str()).unwrap();
-        let value2 = serde_json::from_str(lines[idx + 1].as_str()).unwrap();
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    type Input = Vec<Value>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+
+        let packets: Vec<Value> = lines.iter().map(|line| {
+            let expected = "a packet of nested lists and integers";
+            let packet = serde_json::from_str(line).map_err(|err| {
+                let offset = err.column().saturating_sub(1);
+                line.error_at(line.get(offset..).unwrap_or(line), expected)
+            })?;
+            if !is_packet(&packet) {
+                return Err(line.error(expected));
+            }
+            Ok(packet)
+        }).collect::<Result<_, _>>()?;
+
+        if !packets.len().is_multiple_of(2) {
+            let end = lines.last().unwrap().number + 1;
+            return Err(ParseError::new(end, 1, "", "a second packet to complete the last pair"));
+        }
+        Ok(packets)
+    }
+
+    fn part1(packets: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(packets).into())
+    }
 
-        match in_right_order(&value1, &value2) {
+    fn part2(packets: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(packets).into())
+    }
+}
+
+pub fn part1(packets: &[Value]) -> u64 {
+    let mut sum: u64 = 0;
+
+    for idx in (0..packets.len()).step_by(2) {
+        match in_right_order(&packets[idx], &packets[idx + 1]) {
             Ordering::Less => {
                 sum += (idx as u64 / 2) + 1;
             },
@@ -70,14 +113,10 @@ pub fn part1(file_path: &str) -> u64 {
     sum
 }
 
-pub fn part2(file_path: &str) -> u64 {
-    let mut lines = get_trimmed_lines(file_path);
-    lines.push("[[2]]".to_string());
-    lines.push("[[6]]".to_string());
-
-    let mut values: Vec<Value> = lines.iter().map(|line| {
-        serde_json::from_str(line.as_str()).unwrap()
-    }).collect();
+pub fn part2(packets: &[Value]) -> u64 {
+    let mut values: Vec<Value> = packets.to_vec();
+    values.push(json!([[2]]));
+    values.push(json!([[6]]));
 
     values.sort_by(in_right_order);
 
@@ -90,4 +129,36 @@ pub fn part2(file_path: &str) -> u64 {
         }
     }).collect();
     (indexes[0] * indexes[1]) as u64
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "[1,1,3,1,1]\n",
+        "[1,1,5,1,1]\n",
+        "\n",
+        "[[1],[2,3,4]]\n",
+        "[[1],4]\n",
+        "\n",
+        "[9]\n",
+        "[[8,7,6]]\n",
+        "\n",
+        "[[4,4],4,4]\n",
+        "[[4,4],4,4,4]\n",
+        "\n",
+        "[7,7,7,7]\n",
+        "[7,7,7]\n",
+        "\n",
+        "[]\n",
+        "[3]\n",
+        "\n",
+        "[[[]]]\n",
+        "[[]]\n",
+        "\n",
+        "[1,[2,[3,[4,[5,6,7]]]],8,9]\n",
+        "[1,[2,[3,[4,[5,6,0]]]],8,9]\n",
+    );
+    let packets = Day13::parse(contents).unwrap();
+    assert_eq!(part1(&packets), 13);
+    assert_eq!(part2(&packets), 140);
+}
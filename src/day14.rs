@@ -1,127 +1,41 @@
-use array2d::{Array2D, Error};
-use crate::common::get_trimmed_lines;
+use crate::animate::{no_frames, Frame, OnFrame};
+use crate::common::{get_trimmed_lines, Coord, Grid};
+use crate::error::ParseError;
 use std::cmp::{ min, max };
 use std::collections::HashSet;
-use std::ops::{Index, IndexMut};
+use crate::solution::{Answer, Part, Solution};
 
-type CoordType = (i64, i64);
-
-struct AdjBoard<T> {
-    board: Array2D<T>,
-    mins: CoordType,
+struct Cave {
+    grid: Grid<char>,
     floor: Option<i64>
 }
 
-impl<T> AdjBoard<T> {
-    fn indices_row_major(&self) -> impl DoubleEndedIterator<Item = CoordType> + Clone + '_ {
-        self.board.indices_row_major().map(|coord| {
-            (coord.0 as i64 + self.mins.0 as i64, coord.1 as i64 + self.mins.1 as i64)
-        })
-    }
-
-    fn row_len(&self) -> i64 {
-        self.board.row_len() as i64
-    }
-
-    fn column_len(&self) -> i64 {
-        self.board.column_len() as i64
-    }
-}
-
-impl AdjBoard<char> {
-    fn filled_with(elem: char, mins: CoordType, maxes: CoordType, floor: Option<i64>) -> AdjBoard<char> {
-        let board = Array2D::filled_with(elem, (maxes.0 + 1 - mins.0) as usize, (maxes.1 + 1 - mins.1) as usize);
-
-        AdjBoard {
-            board,
-            mins,
-            floor
-        }
-    }
-
-    fn get(&self, i: CoordType) -> Option<&char> {
-        let row = (i.0 as i64 - self.mins.0 as i64);
-        let col = (i.1 as i64 - self.mins.1 as i64);
+impl Cave {
+    // Without a floor everything outside the scan is the abyss, which is None
+    fn get(&self, i: Coord) -> Option<&char> {
         match self.floor {
-            None => self.board.get(row as usize, col as usize),
-            Some(_floor) => {
-                if i.0 == _floor {
-                    Some(&'#')
-                } else {
-                    match self.board.get(row as usize, col as usize) {
-                        Some(_val) => {
-                            Some(_val)
-                        },
-                        Error => {
-                            Some(&'.')
-                        }
-                    }
-                }
-            }
+            None => self.grid.get(i),
+            Some(_floor) if i.0 == _floor => Some(&'#'),
+            Some(_) => Some(self.grid.get_or_fill(i)),
         }
     }
 
-    fn set(&mut self, i: CoordType, elem: char) -> Result<(), Error> {
-        let row = (i.0 as i64 - self.mins.0 as i64);
-        let col = (i.1 as i64 - self.mins.1 as i64);
-
-        match self.board.get(row as usize, col as usize) {
-            Some('.') => {
-                // good
-            },
-            Some(_val) => {
-                if *_val != elem && !(*_val == '+' && elem == 'o'){
-                    panic!("Unexpected set");
-                }
+    fn set(&mut self, i: Coord, elem: char) {
+        match self.grid.get(i) {
+            Some(_val) if *_val != '.' && *_val != elem && !(*_val == '+' && elem == 'o') => {
+                panic!("Unexpected set");
             },
-            None => {
-                // handle further down
+            _ => {
+                // empty, or outside the grid which grows to fit
             }
         };
 
-        let ret = self.board.set(row as usize, col as usize, elem);
-        match ret {
-            Ok(_ret) => ret,
-            Error => {
-                match self.floor {
-                    None => Error,
-                    Some(_floor) => {
-                        let mins = (
-                            min(self.mins.0, i.0),
-                            min(self.mins.1, i.1),
-                        );
-
-                        let maxes = (
-                            max(self.board.row_len() as i64 - 1 + self.mins.0, i.0),
-                            max(self.board.column_len() as i64 - 1 + self.mins.1, i.1)
-                        );
-
-                        let mut new_board = AdjBoard::filled_with('.', mins, maxes, self.floor);
-                        for coord in self.indices_row_major() {
-                            let val =
-                                if coord == i {
-                                    elem
-                                } else {
-                                    *self.get(coord).unwrap()
-                                };
-
-                            if val != '.' {
-                                new_board.set(coord, val).unwrap();
-                            }
-                        }
-                        self.board = new_board.board;
-                        self.mins = new_board.mins;
-
-                        Ok(())
-                    }
-                }
-            }
-        }
+        self.grid.set(i, elem);
     }
 }
 
-fn make_board(pairs: &Vec<Vec<CoordType>>, floor: Option<i64>) -> AdjBoard<char> {
-    let mut flat_pairs: Vec<&CoordType> = pairs.iter().flatten().collect();
+fn make_board(pairs: &Vec<Vec<Coord>>, floor: Option<i64>) -> Cave {
+    let mut flat_pairs: Vec<&Coord> = pairs.iter().flatten().collect();
     let source = (0, 500);
     flat_pairs.push(&source);
     let maxes = (
@@ -134,25 +48,25 @@ fn make_board(pairs: &Vec<Vec<CoordType>>, floor: Option<i64>) -> AdjBoard<char>
     );
     //println!("maxes mins {:?} {:?}", maxes, mins);
 
-    let mut board = AdjBoard::filled_with('.', mins, maxes, floor);
-    board.set(source, '+').unwrap();
+    let mut board = Cave {
+        grid: Grid::filled_with('.', mins, maxes),
+        floor
+    };
+    board.set(source, '+');
     for list in pairs {
-        let mut prev: Option<CoordType> = None;
+        let mut prev: Option<Coord> = None;
         for pair in list {
-            match prev {
-                Some(_prev) => {
-                    let start0 = min(_prev.0, pair.0);
-                    let end0 = max(_prev.0, pair.0) + 1;
-                    let start1 = min(_prev.1, pair.1);
-                    let end1 = max(_prev.1, pair.1) + 1;
-
-                    for coord0 in start0..end0 {
-                        for coord1 in start1..end1 {
-                            board.set((coord0, coord1), '#').unwrap();
-                        }
+            if let Some(_prev) = prev {
+                let start0 = min(_prev.0, pair.0);
+                let end0 = max(_prev.0, pair.0) + 1;
+                let start1 = min(_prev.1, pair.1);
+                let end1 = max(_prev.1, pair.1) + 1;
+
+                for coord0 in start0..end0 {
+                    for coord1 in start1..end1 {
+                        board.set((coord0, coord1), '#');
                     }
-                },
-                None => {}
+                }
             }
             prev = Some(*pair);
         }
@@ -160,54 +74,94 @@ fn make_board(pairs: &Vec<Vec<CoordType>>, floor: Option<i64>) -> AdjBoard<char>
     board
 }
 
-fn print_board(board: &AdjBoard<char>) {
-    let row_range = match board.floor {
-        Some(_floor) => -3..board.row_len() + 3,
-        None => 0..board.row_len()
-    };
-
-    for row in row_range {
-        let col_range = match board.floor {
-            Some(_floor) => -3..board.column_len() + 3,
-            None => 0..board.column_len(),
-        };
-
-        for col in col_range {
-            print!("{}", board.get((row + board.mins.0, col + board.mins.1)).unwrap());
+// The cave with the grain of sand which is falling, and the floor if there is one
+fn cave_frame(board: &Cave, falling: Coord, grains: usize) -> Frame {
+    let mut grid = board.grid.clone();
+    if let Some(floor) = board.floor {
+        let (mins, maxes) = (grid.mins(), grid.maxes());
+        for col in mins.1 - 1..=maxes.1 + 1 {
+            grid.set((floor, col), '#');
         }
-        println!();
     }
-
-    println!();
-    println!();
+    grid.set(falling, 'o');
+    Frame {
+        grid,
+        focus: Some(falling),
+        caption: format!("{} grains of sand at rest", grains),
+    }
 }
 
-fn make_pairs(file_path: &str) -> Vec<Vec<CoordType>> {
-    let lines = get_trimmed_lines(&file_path);
+pub fn make_pairs(contents: &str) -> Result<Vec<Vec<Coord>>, ParseError> {
+    let lines = get_trimmed_lines(contents);
+    if lines.is_empty() {
+        return Err(ParseError::new(1, 1, "", "a path of rock like 498,4 -> 498,6"));
+    }
     lines.iter().map(|line| {
-        let tup_list: Vec<CoordType> = line.split(" -> ").map(|pair| {
-            let mut split = pair.split(",");
-            let tup0 = split.next().unwrap();
-            let tup1 = split.next().unwrap();
-            (tup1.parse().unwrap(), tup0.parse().unwrap())
-        }).collect();
-        tup_list
+        let mut tup_list: Vec<Coord> = Vec::new();
+        for pair in line.split(" -> ") {
+            let tup = match pair.split(',').collect::<Vec<&str>>()[..] {
+                [tup0, tup1] => (line.parse_at(tup1, "a y coordinate")?, line.parse_at(tup0, "an x coordinate")?),
+                _ => return Err(line.error_at(pair, "a point like 498,4"))
+            };
+            if let Some(prev) = tup_list.last() {
+                if prev.0 != tup.0 && prev.1 != tup.1 {
+                    return Err(line.error_at(pair, "a point in a horizontal or vertical line from the previous one"));
+                }
+                // sand pours in at 500,0, so rock can't be there
+                if (min(prev.0, tup.0)..=max(prev.0, tup.0)).contains(&0) && (min(prev.1, tup.1)..=max(prev.1, tup.1)).contains(&500) {
+                    return Err(line.error_at(pair, "a path of rock which doesn't cover the sand source at 500,0"));
+                }
+            }
+            tup_list.push(tup);
+        }
+        Ok(tup_list)
     }).collect()
 }
 
 #[derive(Debug)]
 enum NextOpts {
     OutOfBounds,
-    Blocked(CoordType),
-    Viable(CoordType)
+    Blocked(Coord),
+    Viable(Coord)
 }
-pub fn part1(file_path: &str) -> usize {
-    let pairs = make_pairs(&file_path);
-    let mut board = make_board(&pairs, None);
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const ANIMATED: bool = true;
+    type Input = Vec<Vec<Coord>>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        make_pairs(contents)
+    }
+
+    fn part1(pairs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(pairs).into())
+    }
+
+    fn part2(pairs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(pairs).into())
+    }
+
+    fn animate(pairs: &Self::Input, _config: &Self::Config, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(match part {
+            Part::One => pour_sand(pairs, on_frame),
+            Part::Two => pour_sand_onto_floor(pairs, on_frame),
+        }.into()))
+    }
+}
+
+pub fn part1(pairs: &Vec<Vec<Coord>>) -> usize {
+    pour_sand(pairs, &mut no_frames)
+}
+
+fn pour_sand(pairs: &Vec<Vec<Coord>>, on_frame: &mut OnFrame) -> usize {
+    let mut board = make_board(pairs, None);
 
     let mut turns = 0;
     loop {
-        let mut current: CoordType = (0, 500);
+        let mut current: Coord = (0, 500);
 
         loop {
             let next: NextOpts = {
@@ -250,31 +204,30 @@ pub fn part1(file_path: &str) -> usize {
                 },
                 NextOpts::Viable(_next) => {
                     current = _next;
+                    on_frame(&|| cave_frame(&board, current, turns));
                 }
             }
         }
 
-        //print_board(&board);
         turns += 1;
     }
 }
 
-pub fn part2(file_path: &str) -> usize {
-    let pairs = make_pairs(&file_path);
+pub fn part2(pairs: &Vec<Vec<Coord>>) -> usize {
+    pour_sand_onto_floor(pairs, &mut no_frames)
+}
+
+fn pour_sand_onto_floor(pairs: &Vec<Vec<Coord>>, on_frame: &mut OnFrame) -> usize {
     let floor = pairs.iter().flatten().map(|pair| pair.0).max().unwrap() + 2;
-    let mut board = make_board(&pairs, Some(floor));
+    let mut board = make_board(pairs, Some(floor));
 
-    let mut turns = 0;
-    let mut s: HashSet<CoordType> = HashSet::new();
+    let mut s: HashSet<Coord> = HashSet::new();
     loop {
-        let mut current: CoordType = (0, 500);
+        let mut current: Coord = (0, 500);
 
         loop {
-            match board.get(current) {
-                Some('o') => {
-                    return s.len();
-                },
-                _ => {},
+            if let Some('o') = board.get(current) {
+                return s.len();
             };
 
             let next: NextOpts = {
@@ -309,20 +262,30 @@ pub fn part2(file_path: &str) -> usize {
                     panic!("infinite floor");
                 },
                 NextOpts::Blocked(_current) => {
-                    board.set(_current, 'o').unwrap();
+                    board.set(_current, 'o');
                     //println!("current, {:?}", current);
                     s.insert(_current);
                     break;
                 },
                 NextOpts::Viable(_next) => {
                     current = _next;
+                    on_frame(&|| cave_frame(&board, current, s.len()));
                 }
             }
         }
 
         //println!("s {}", s.len() - 1);
-        // print_board(&board);
-        turns += 1;
     }
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "498,4 -> 498,6 -> 496,6\n",
+        "503,4 -> 502,4 -> 502,9 -> 494,9\n",
+    );
+    let pairs = Day14::parse(contents).unwrap();
+    assert_eq!(part1(&pairs), 24);
+    assert_eq!(part2(&pairs), 93);
+}
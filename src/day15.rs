@@ -1,20 +1,24 @@
 use itertools::Itertools;
 use lazy_static::lazy_static;
+use log::debug;
 use regex::Regex;
 use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::params::{self, parse_value, ParamError};
 use std::cmp::max;
+use crate::solution::{Answer, Solution};
 
 #[derive(Debug)]
-struct Sensor {
-    x: i64,
-    y: i64,
-    bx: i64,
-    by: i64,
+pub struct Sensor {
+    pub x: i64,
+    pub y: i64,
+    pub bx: i64,
+    pub by: i64,
 }
 
 
-fn read_sensors(file_path: &str) -> Vec<Sensor> {
-    let lines = get_trimmed_lines(&file_path);
+pub fn read_sensors(contents: &str) -> Result<Vec<Sensor>, ParseError> {
+    let lines = get_trimmed_lines(contents);
 
     lazy_static! {
         static ref RE: Regex = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();
@@ -23,15 +27,16 @@ fn read_sensors(file_path: &str) -> Vec<Sensor> {
     lines.iter().map(|line| {
         match RE.captures(line) {
             Some(cap) => {
-                Sensor {
-                    x: cap.get(1).unwrap().as_str().parse().unwrap(),
-                    y: cap.get(2).unwrap().as_str().parse().unwrap(),
-                    bx: cap.get(3).unwrap().as_str().parse().unwrap(),
-                    by: cap.get(4).unwrap().as_str().parse().unwrap(),
-                }
+                let coordinate = |idx| line.parse_at(cap.get(idx).unwrap().as_str(), "a coordinate");
+                Ok(Sensor {
+                    x: coordinate(1)?,
+                    y: coordinate(2)?,
+                    bx: coordinate(3)?,
+                    by: coordinate(4)?,
+                })
             },
             None => {
-                panic!("unexpected line")
+                Err(line.error("\"Sensor at x=<x>, y=<y>: closest beacon is at x=<x>, y=<y>\""))
             }
         }
     }).collect()
@@ -57,24 +62,24 @@ fn test_sort_filter_exclusions() {
 fn test_combine_exclusions() {
     let input: Vec<(i64, i64)> = vec![(-2, 3), (3, 3), (3, 15), (15, 15), (15, 19), (19, 25)];
     assert_eq!(combine_exclusions(&input), vec![(-2, 25)]);
-    assert_eq!(combine_exclusions(&vec![(1i64,3i64), (3i64, 5i64), (6i64, 9i64)]), vec![(1,5), (6,9)]);
+    assert_eq!(combine_exclusions(&[(1i64,3i64), (3i64, 5i64), (6i64, 9i64)]), vec![(1,5), (6,9)]);
 }
 
-fn sort_filter_exclusions(exclusions: &Vec<(i64, i64)>) -> Vec<(i64, i64)> {
-    let mut copy: Vec<(i64, i64)> = exclusions.clone();
+fn sort_filter_exclusions(exclusions: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut copy: Vec<(i64, i64)> = exclusions.to_vec();
     copy.sort();
 
     let mut prev = None;
-    let mut filtered: Vec<(i64, i64)> = copy.iter().filter_map(|pair| {
+    let mut filtered: Vec<(i64, i64)> = copy.iter().map(|pair| {
         match prev {
             None => {
                 prev = Some(*pair);
-                Some(*pair)
+                *pair
             },
             Some(mut _prev) => {
                 let result = (max(_prev.1, pair.0), max(_prev.1, pair.1));
                 prev = Some(result);
-                Some(result)
+                result
             }
         }
     }).collect();
@@ -82,21 +87,17 @@ fn sort_filter_exclusions(exclusions: &Vec<(i64, i64)>) -> Vec<(i64, i64)> {
     filtered
 }
 
-fn combine_exclusions(exclusions: &Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+fn combine_exclusions(exclusions: &[(i64, i64)]) -> Vec<(i64, i64)> {
     let mut combined = Vec::new();
     for current in exclusions.iter() {
         let prev: Option<(i64, i64)> = combined.last().cloned();
         match prev {
-            None => {
-                combined.push(*current);
+            Some(_prev) if _prev.1 == current.0 => {
+                combined.pop();
+                combined.push((_prev.0, current.1));
             },
-            Some(_prev) => {
-                if _prev.1 == current.0 {
-                    combined.pop();
-                    combined.push((_prev.0, current.1));
-                } else {
-                    combined.push(*current);
-                }
+            _ => {
+                combined.push(*current);
             }
         }
     }
@@ -105,13 +106,13 @@ fn combine_exclusions(exclusions: &Vec<(i64, i64)>) -> Vec<(i64, i64)> {
 
 }
 
-fn calc_exclusions(sensors: &Vec<Sensor>, row: i64) -> Vec<(i64, i64)> {
+pub fn calc_exclusions(sensors: &[Sensor], row: i64) -> Vec<(i64, i64)> {
     let exclusions: Vec<(i64, i64)> = sensors.iter().filter_map(|sensor| {
         let distance = (sensor.x - sensor.bx).abs() + (sensor.y - sensor.by).abs();
         if (row - sensor.y).abs() > distance {
             None
         } else {
-            let dx = (distance - (row - sensor.y).abs());
+            let dx = distance - (row - sensor.y).abs();
             Some((sensor.x - dx, sensor.x + dx + 1))
         }
     }).collect();
@@ -121,17 +122,17 @@ fn calc_exclusions(sensors: &Vec<Sensor>, row: i64) -> Vec<(i64, i64)> {
     combine_exclusions(&sorted_filter_exclusions)
 }
 
-fn count_exclusions(sensors: &Vec<Sensor>, row: i64) -> i64 {
+pub fn count_exclusions(sensors: &[Sensor], row: i64) -> i64 {
     let sorted_exclusions = calc_exclusions(sensors, row);
-    println!("{:?}", sorted_exclusions);
+    debug!("{:?}", sorted_exclusions);
     let sum: i64 = sorted_exclusions.iter().map(|exclusion| {
         exclusion.1 - exclusion.0
     }).sum();
     sum - sensors.iter().filter_map(|sensor| {
         // println!("{:?} {}", sensor, row);
-        if sensor.y == row && !contains_point(&sorted_exclusions, sensor.x).is_none() {
+        if sensor.y == row && contains_point(&sorted_exclusions, sensor.x).is_some() {
             Some((sensor.x, sensor.y))
-        } else if sensor.by == row && !contains_point(&sorted_exclusions, sensor.bx).is_none() {
+        } else if sensor.by == row && contains_point(&sorted_exclusions, sensor.bx).is_some() {
             Some((sensor.bx, sensor.by))
         } else {
             None
@@ -139,8 +140,8 @@ fn count_exclusions(sensors: &Vec<Sensor>, row: i64) -> i64 {
     }).unique().count() as i64
 }
 
-fn contains_point(exclusions: &Vec<(i64, i64)>, col: i64) -> Option<usize> {
-    let key = exclusions.binary_search_by_key(&col, |&(a, b)| a);
+fn contains_point(exclusions: &[(i64, i64)], col: i64) -> Option<usize> {
+    let key = exclusions.binary_search_by_key(&col, |&(a, _b)| a);
 
     match key {
         Ok(idx) => {
@@ -161,22 +162,66 @@ fn contains_point(exclusions: &Vec<(i64, i64)>, col: i64) -> Option<usize> {
     }
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let sensors = read_sensors(file_path);
+// The example uses row 10 and bound 20
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub row: i64,
+    pub bound: i64,
+}
 
-    //count_exclusions(&sensors, 10)
-    count_exclusions(&sensors, 2000000)
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            row: 2000000,
+            bound: 4000000,
+        }
+    }
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let sensors = read_sensors(file_path);
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["row", "bound"];
 
-    let bound = 4000000; //20;
-    for y in 0..bound {
-        let exclusions = calc_exclusions(&sensors, y);
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "row" => self.row = parse_value(key, value, "a row number")?,
+            "bound" => self.bound = parse_value(key, value, "the largest x and y of the distress beacon")?,
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
+        }
+        Ok(())
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    type Input = Vec<Sensor>;
+    type Config = Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_sensors(contents)
+    }
+
+    fn part1(sensors: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(sensors, config).into())
+    }
+
+    fn part2(sensors: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(sensors, config)?.into())
+    }
+}
+
+pub fn part1(sensors: &[Sensor], config: &Config) -> i64 {
+    count_exclusions(sensors, config.row)
+}
+
+// The beacon is somewhere in 0..=bound on both axes, in the only spot no sensor can see
+pub fn find_distress_beacon(sensors: &[Sensor], bound: i64) -> Option<(i64, i64)> {
+    for y in 0..=bound {
+        let exclusions = calc_exclusions(sensors, y);
 
         let mut x = 0;
-        while x < bound {
+        while x <= bound {
             //println!("x {}", x);
             match contains_point(&exclusions, x) {
                 Some(idx) => {
@@ -184,14 +229,48 @@ pub fn part2(file_path: &str) -> i64 {
                     x = exclusions[idx].1;
                 },
                 None => {
-                    println!("found {} {}", x, y);
-                    return x*bound + y;
+                    return Some((x, y));
                 }
             }
         }
     }
 
-    0
-    //count_exclusions(&sensors, 10)
-    //count_exclusions(&sensors, 2000000)
-}
\ No newline at end of file
+    None
+}
+
+pub fn tuning_frequency(beacon: (i64, i64)) -> i64 {
+    beacon.0 * 4000000 + beacon.1
+}
+
+pub fn part2(sensors: &[Sensor], config: &Config) -> Result<i64, ParseError> {
+    // the sensors cover the whole area, which isn't down to any one of them
+    let beacon = find_distress_beacon(sensors, config.bound).ok_or_else(|| {
+        ParseError::new(0, 0, "", &format!("a spot within 0..={} which no sensor can see for the distress beacon", config.bound))
+    })?;
+    Ok(tuning_frequency(beacon))
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n",
+        "Sensor at x=9, y=16: closest beacon is at x=10, y=16\n",
+        "Sensor at x=13, y=2: closest beacon is at x=15, y=3\n",
+        "Sensor at x=12, y=14: closest beacon is at x=10, y=16\n",
+        "Sensor at x=10, y=20: closest beacon is at x=10, y=16\n",
+        "Sensor at x=14, y=17: closest beacon is at x=10, y=16\n",
+        "Sensor at x=8, y=7: closest beacon is at x=2, y=10\n",
+        "Sensor at x=2, y=0: closest beacon is at x=2, y=10\n",
+        "Sensor at x=0, y=11: closest beacon is at x=2, y=10\n",
+        "Sensor at x=20, y=14: closest beacon is at x=25, y=17\n",
+        "Sensor at x=17, y=20: closest beacon is at x=21, y=22\n",
+        "Sensor at x=16, y=7: closest beacon is at x=15, y=3\n",
+        "Sensor at x=14, y=3: closest beacon is at x=15, y=3\n",
+        "Sensor at x=20, y=1: closest beacon is at x=15, y=3\n",
+    );
+    let sensors = Day15::parse(contents).unwrap();
+    let config = Config { row: 10, bound: 20 };
+    assert_eq!(part1(&sensors, &config), 26);
+    assert_eq!(part2(&sensors, &config), Ok(56000011));
+    assert_eq!(part2(&sensors, &Config { row: 10, bound: 5 }).unwrap_err().to_string(), "expected a spot within 0..=5 which no sensor can see for the distress beacon, found end of input");
+}
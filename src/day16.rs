@@ -1,522 +1,440 @@
-use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fmt;
-use std::fmt::Formatter;
-use clap::value_parser;
-use itertools::Itertools;
+use std::cmp::{max, Reverse};
+use std::collections::HashMap;
+use std::mem;
+use std::num::{NonZeroU32, NonZeroUsize};
 use lazy_static::lazy_static;
+use log::{debug, trace};
 use regex::Regex;
-use crate::common::{ get_trimmed_lines};
+use crate::common::{floyd_warshall, get_trimmed_lines, Cost, Line};
+use crate::error::ParseError;
+use crate::params::{self, parse_value, ParamError};
+use crate::solution::{Answer, Solution};
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
-pub struct CoordType {
-    letter1: u8,
-    letter2: u8,
-    is_valve: bool
-}
-
-impl fmt::Debug for CoordType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}{}", self.letter1.escape_ascii(), self.letter2.escape_ascii(), if self.is_valve { "+" } else { "" })
-    }
-}
+const START: &str = "AA";
 
-type CostType = f64;
+// Valves are numbered in the order they're listed in the input
+pub type ValveId = usize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Valve {
-    name: CoordType,
-    flow_rate: i64,
-    next_valves: Vec<CoordType>
-}
-
-fn to_valve_key(key: &str) -> CoordType {
-    let b: Vec<u8> = key.bytes().collect();
-    if b.len() == 2 {
-        CoordType { letter1: b[0], letter2: b[1], is_valve: false }
-    } else {
-        CoordType { letter1: b[0], letter2: b[1], is_valve: true }
-    }
+    pub name: String,
+    pub flow_rate: i64,
+    pub next_valves: Vec<ValveId>
 }
 
-fn read_valves(file_path: &str) -> Vec<Valve> {
+pub fn read_valves(contents: &str) -> Result<Vec<Valve>, ParseError> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r"Valve ([A-Z]+) has flow rate=(\d+); tunnels? leads? to valves? (.+)").unwrap();
     }
 
-    let lines = get_trimmed_lines(file_path);
-    lines.iter().map(|line| {
-        match RE.captures(line) {
-            Some(cap) => {
-                Valve {
-                    name: to_valve_key(cap.get(1).unwrap().as_str()),
-                    flow_rate: cap.get(2).unwrap().as_str().parse().unwrap(),
-                    next_valves: cap.get(3).unwrap().as_str().split(", ").map(|s| to_valve_key(s)).collect()
-                }
-            },
-            None => {
-                panic!("unknown line {}", line)
-            }
+    let lines = get_trimmed_lines(contents);
+    let valve_name = |line: &Line, key: &str| {
+        if key.len() == 2 && key.bytes().all(|b| b.is_ascii_uppercase()) {
+            Ok(())
+        } else {
+            Err(line.error_at(key, "a valve name of two capital letters"))
         }
-    }).collect()
-}
+    };
 
+    let captures = lines.iter().map(|line| {
+        RE.captures(line).map(|cap| (line, cap)).ok_or_else(|| line.error("\"Valve <name> has flow rate=<rate>; tunnels lead to valves <names>\""))
+    }).collect::<Result<Vec<_>, _>>()?;
+
+    // every valve gets its id before any tunnels are read, since they can lead further down the input
+    let mut ids: HashMap<&str, ValveId> = HashMap::new();
+    for (id, (line, cap)) in captures.iter().enumerate() {
+        let name = cap.get(1).unwrap().as_str();
+        valve_name(line, name)?;
+        if ids.insert(name, id).is_some() {
+            return Err(line.error_at(name, "a valve which isn't listed already"));
+        }
+    }
+    if !ids.contains_key(START) {
+        let end = lines.last().map(|line| line.number + 1).unwrap_or(1);
+        return Err(ParseError::new(end, 1, "", "a line for the starting valve AA"));
+    }
 
-fn reconstruct_path(came_from: &HashMap<CoordType, CoordType>, current: CoordType) -> VecDeque<CoordType> {
-    let mut total_path: VecDeque<CoordType> = VecDeque::new();
-    let mut current_mut = current;
-    while came_from.contains_key(&current_mut) {
-        current_mut = came_from[&current_mut];
-        total_path.push_front(current_mut);
+    let valves = captures.iter().map(|(line, cap)| {
+        Ok(Valve {
+            name: cap[1].to_string(),
+            flow_rate: line.parse_at(cap.get(2).unwrap().as_str(), "a flow rate")?,
+            next_valves: cap.get(3).unwrap().as_str().split(", ").map(|key| {
+                valve_name(line, key)?;
+                ids.get(key).copied().ok_or_else(|| line.error_at(key, "a tunnel to a valve listed in the input"))
+            }).collect::<Result<_, _>>()?
+        })
+    }).collect::<Result<Vec<_>, _>>()?;
+
+    // sets of open valves are bitmasks
+    let mut with_flow = captures.iter().zip(valves.iter()).filter(|(_capture, valve)| valve.flow_rate > 0);
+    if let Some(((line, cap), _valve)) = with_flow.nth(64) {
+        return Err(line.error_at(cap.get(2).unwrap().as_str(), "a flow rate of 0, at most 64 valves can have flow"));
     }
-    return total_path;
+    Ok(valves)
 }
 
-pub fn dijkstra(valves_map: &HashMap<CoordType, Valve>, source: CoordType) -> HashMap<CoordType, CoordType> {
-    let valve_keys: Vec<CoordType> = valves_map.keys().map(|key| [*key, CoordType {
-        letter1: key.letter1,
-        letter2: key.letter2,
-        is_valve: true,
-    }]).flatten().collect();
-    let mut dist: HashMap<CoordType, f64> = valve_keys.iter().map(|key| (*key, f64::INFINITY)).collect();
-    let mut prev: HashMap<CoordType, CoordType> = HashMap::new();
-    let mut queue: HashSet<CoordType> = valve_keys.iter().map(|key| *key).collect();
-
-    let next_valves: HashMap<CoordType, (Vec<(CoordType, f64)>)> = valve_keys.iter().map(|key| {
-        if !key.is_valve {
-            let mut next_valves: Vec<(CoordType, f64)> = Vec::new();
-            let valve = &valves_map[key];
-            for _next in valve.next_valves.iter() {
-                next_valves.push((*_next, 1f64));
-            }
-            next_valves.push((CoordType {
-                letter1: key.letter1,
-                letter2: key.letter2,
-                is_valve: true
-            }, 0.5f64));
-            (*key, next_valves)
-        } else {
-            let mut next_valves: Vec<(CoordType, f64)> = Vec::new();
-            let valve = &valves_map[&CoordType {
-                letter1: key.letter1,
-                letter2: key.letter2,
-                is_valve: false
-            }];
-            for _next in valve.next_valves.iter() {
-                next_valves.push((*_next, 1.5f64));
-            }
-            (*key, next_valves)
-        }
-    }).collect();
+fn start(valves: &[Valve]) -> ValveId {
+    // read_valves made sure there is one
+    valves.iter().position(|valve| valve.name == START).unwrap()
+}
 
-    dist.insert(source,0f64);
-
-    while !queue.is_empty() {
-        let u = {
-            let mut lowest: Option<(CoordType, f64)> = None;
-
-            for vertex in queue.iter() {
-                match lowest {
-                    None => {
-                        lowest = Some((*vertex, dist[&vertex]));
-                    },
-                    Some(_lowest) => {
-                        if dist[&vertex] < _lowest.1 {
-                            lowest = Some((*vertex, dist[&vertex]));
-                        }
-                    }
-                }
-            }
+// Minutes to walk from any valve to any other
+fn tunnel_distances(valves: &[Valve]) -> Vec<Vec<Option<Cost>>> {
+    let tunnels: Vec<Vec<(usize, Cost)>> = valves.iter().map(|valve| valve.next_valves.iter().map(|next| (*next, 1)).collect()).collect();
+    floyd_warshall(&tunnels)
+}
 
-            lowest.unwrap().0
-        };
+// The valves with no flow are only ever walked through, so all that matters is how long it takes to walk
+// between the valves worth opening and from the start to them
+#[derive(Debug, Clone)]
+pub struct ValveGraph {
+    // the valves with flow and lastly the start, so bit idx of a set of opened valves is valves[idx]
+    pub valves: Vec<ValveId>,
+    pub flow_rates: Vec<i64>,
+    // minutes to walk between any two of valves, or None if no tunnels lead there
+    pub distances: Vec<Vec<Option<i64>>>,
+}
 
-        queue.remove(&u);
+impl ValveGraph {
+    pub fn new(valves: &[Valve], start: ValveId) -> ValveGraph {
+        let distances = tunnel_distances(valves);
 
-        for (v, cost) in next_valves[&u].iter() {
-            if !queue.contains(&v) {
-                continue;
-            }
-
-            let alt = dist[&u] + cost;
-            if alt < dist[&v] {
-                dist.insert(*v, alt);
-                prev.insert(*v, u);
-            }
+        let ids: Vec<ValveId> = (0..valves.len()).filter(|id| valves[*id].flow_rate > 0).chain([start]).collect();
+        debug!("valves {} {:?}", ids.len() - 1, ids.iter().map(|id| &valves[*id].name).collect::<Vec<_>>());
+        ValveGraph {
+            flow_rates: ids.iter().map(|id| valves[*id].flow_rate).collect(),
+            distances: ids.iter().map(|from| ids.iter().map(|to| distances[*from][*to].map(|minutes| minutes as i64)).collect()).collect(),
+            valves: ids,
         }
     }
-    //println!("prev {:?}", prev);
-    //println!("dist {:?}", dist);
-
-    /*
-    let path = {
-        let mut current = source;
-        let mut path: VecDeque<CoordType> = [current].iter().map(|item| *item).collect();
-
-        loop {
-            println!("{:?}", current);
-            match prev.get(&current) {
-                Some(_prev) => {
-                    path.push_back(*_prev);
-                    current = *_prev;
-                },
-                None => {
-                    break;
-                }
-            }
-        }
 
-        path
-    };
-*/
-    prev
+    // where the start is in valves, which is also how many valves are worth opening
+    pub fn start(&self) -> usize {
+        self.valves.len() - 1
+    }
 }
 
+// The most pressure one agent can release in total_minutes for every set of valves it could open, keyed by a
+// bitmask over the graph's valves. Opening a valve always takes time, so sweeping from the most minutes left
+// down expands each state of where the agent is, how long is left and what's open exactly once.
+fn best_flows(graph: &ValveGraph, total_minutes: i64) -> HashMap<u64, i64> {
+    let openable = graph.start();
+    let positions = graph.valves.len();
+    // minutes from each valve to having opened each valve with flow
+    let minutes_to: Vec<Vec<Option<i64>>> = graph.distances.iter().map(|row| {
+        row[..openable].iter().map(|minutes| minutes.map(|minutes| minutes + 1)).collect()
+    }).collect();
 
-fn make_path(valves_with_flow: &Vec<CoordType>, paths: &HashMap<CoordType, HashMap<CoordType, CoordType>>, start: CoordType) -> Vec<CoordType> {
-    let mut path= Vec::new();
-    let mut prev = start;
-    for valve in valves_with_flow.iter() {
-        for piece in reconstruct_path(&paths[&prev], *valve) {
-            path.push(piece);
-        }
-        prev = *valve;
+    let mut best: HashMap<u64, i64> = HashMap::from([(0, 0)]);
+    // the most released for each set of open valves, indexed by minutes left and then position
+    let mut states: Vec<HashMap<u64, i64>> = vec![HashMap::new(); (total_minutes.max(0) as usize + 1) * positions];
+    if total_minutes > 0 {
+        states[total_minutes as usize * positions + graph.start()].insert(0, 0);
     }
-    path.push(prev);
-    path.remove(0);
-    path
-}
-
-fn calc_flow(path: &Vec<CoordType>, valves_map: &HashMap<CoordType, Valve>) -> i64 {
-    //println!("{:?}", path);
-
-    let mut cum_flow = 0;
-    let mut flow = 0;
-    for minute in 0..30 {
-        cum_flow += flow;
-
-        match path.get(minute) {
-            None => {
-                //println!("Minute {}: nothing to do", minute);
-            },
-            Some(item) => {
-                if item.is_valve {
-                    let valve = &valves_map[&CoordType {
-                        letter1: item.letter1,
-                        letter2: item.letter2,
-                        is_valve: false,
-                    }];
-
-                    flow += valve.flow_rate;
-                    //println!("Minute {}: opened valve {:?} to release {}", minute, item, valve.flow_rate);
-                } else {
-                    //println!("Minute {}: moved to item {:?}", minute, item);
+    let mut expanded = 0;
+    for minutes_left in (1..=total_minutes).rev() {
+        for position in 0..positions {
+            let here = mem::take(&mut states[minutes_left as usize * positions + position]);
+            expanded += here.len();
+            for (opened, flow) in here {
+                let best_flow = best.entry(opened).or_insert(0);
+                *best_flow = max(*best_flow, flow);
+
+                for (idx, flow_rate) in graph.flow_rates[..openable].iter().enumerate() {
+                    let minutes_left = match minutes_to[position][idx] {
+                        Some(minutes) => minutes_left - minutes,
+                        None => continue,
+                    };
+                    if opened & (1 << idx) != 0 || minutes_left <= 0 {
+                        continue;
+                    }
+                    let next_flow = states[minutes_left as usize * positions + idx].entry(opened | (1 << idx)).or_insert(0);
+                    *next_flow = max(*next_flow, flow + minutes_left * flow_rate);
                 }
             }
         }
-
-        //println!("cumulative flow is {}, flow is {}", cum_flow, flow);
     }
-
-    cum_flow
+    trace!("{} states, {} sets of open valves", expanded, best.len());
+    best
 }
 
-fn find_best_path(valves_with_flow: &Vec<Valve>, distances: &HashMap<(CoordType, CoordType), i64>, start: CoordType) -> i64 {
-    struct State {
-        path: Vec<CoordType>,
-        minutes: i64,
-        flow: i64
+// Gives each of agents_left agents a set from flows, starting from idx, adding to the sets of valves already
+// picked. Agents are alike so each takes a set no better than the one before. A branch stops as soon as neither
+// giving every agent left the best set there is left, nor opening every valve left as early as possible, could
+// beat the best plan so far.
+fn pick_sets(flows: &[(u64, i64)], singles: &[i64], idx: usize, agents_left: usize, picked: &mut Vec<u64>, flow: i64, best: &mut (i64, Vec<u64>)) {
+    if agents_left == 0 {
+        if flow > best.0 {
+            *best = (flow, picked.clone());
+        }
+        return;
     }
 
-    let mut old_moves = vec![State {
-        path: vec![start],
-        minutes: 0,
-        flow: 0
-    }];
-    let mut solutions: Vec<i64> = vec![];
-
-    println!("distances {} {:?}", distances.len(), distances);
-    println!("valves {} {:?}", valves_with_flow.len(), valves_with_flow);
-    println!("start {:?}", start);
-
-    loop {
-        if old_moves.is_empty() {
-            let ret = solutions.iter().max().unwrap();
-            return *ret;
+    let opened = picked.iter().fold(0, |opened, set| opened | set);
+    let valves_left: i64 = singles.iter().enumerate().filter(|(valve, _flow)| opened & (1 << valve) == 0).map(|(_valve, flow)| flow).sum();
+    for (next_idx, (set, set_flow)) in flows.iter().enumerate().skip(idx) {
+        if flow + (set_flow * agents_left as i64).min(valves_left) <= best.0 {
+            break;
         }
-        println!("old_moves {}", old_moves.len());
-        let new_moves: Vec<State> = old_moves.iter().filter_map(|old_state| {
-            if old_state.minutes >= 30 {
-                return None
-            }
+        // agents working at once never open the same valve
+        if set & opened != 0 {
+            continue;
+        }
+        picked.push(*set);
+        pick_sets(flows, singles, next_idx, agents_left - 1, picked, flow + set_flow, best);
+        picked.pop();
+    }
+}
 
-            let mut possibilities: Vec<_> = valves_with_flow.iter().filter_map(|valve| {
-                if old_state.path.contains(&valve.name) {
-                    return None;
-                }
+// The set of valves each agent opens in the best plan for the team. Every agent starts in the same place with
+// the same time, so they can each do what one agent could alone.
+fn best_team(flows: &HashMap<u64, i64>, agents: usize) -> Vec<u64> {
+    // the most each valve can release, which is when it's the only one opened
+    let valves = flows.keys().map(|opened| 64 - opened.leading_zeros() as usize).max().unwrap_or(0);
+    let singles: Vec<i64> = (0..valves).map(|valve| flows.get(&(1 << valve)).copied().unwrap_or(0)).collect();
 
-                let minutes = old_state.minutes + distances[&(*old_state.path.last().unwrap(), valve.name)];
-                let flow = ((30 - minutes) * valve.flow_rate) + old_state.flow;
-                let mut new_move_path = old_state.path.clone();
-                new_move_path.push(valve.name);
-                Some(State {
-                    path: new_move_path,
-                    minutes,
-                    flow
-                })
-            }).collect();
-            possibilities.push(
-                State {
-                    path: old_state.path.clone(),
-                    minutes: 30,
-                    flow: old_state.flow
-                }
-            );
-            Some(possibilities)
-        }).flatten().collect();
+    let mut flows: Vec<(u64, i64)> = flows.iter().map(|(opened, flow)| (*opened, *flow)).collect();
+    flows.sort_by_key(|(_opened, flow)| Reverse(*flow));
 
-        for new_move in new_moves.iter() {
-            if new_move.minutes >= 30 {
-                solutions.push(new_move.flow);
-            }
-        }
+    // nobody opening anything is always possible
+    let mut best = (0, vec![0; agents]);
+    pick_sets(&flows, &singles, 0, agents, &mut Vec::new(), 0, &mut best);
+    best.1
+}
 
-        old_moves = new_moves;
+// The minutes left after opening each valve an agent opens, in order, along with the index of the valve in the
+// graph
+type Route = Vec<(i64, usize)>;
+
+// The best order to open the valves in to_open in, which is how best_flows got to them
+fn best_route(graph: &ValveGraph, position: usize, minutes_left: i64, to_open: u64) -> (i64, Route) {
+    let mut best = (0, Vec::new());
+    for (idx, flow_rate) in graph.flow_rates[..graph.start()].iter().enumerate() {
+        let minutes_left = match graph.distances[position][idx] {
+            Some(minutes) => minutes_left - minutes - 1,
+            None => continue,
+        };
+        if to_open & (1 << idx) == 0 || minutes_left <= 0 {
+            continue;
+        }
+        let (flow, route) = best_route(graph, idx, minutes_left, to_open & !(1 << idx));
+        if flow + minutes_left * flow_rate > best.0 {
+            best = (flow + minutes_left * flow_rate, [(minutes_left, idx)].into_iter().chain(route).collect());
+        }
     }
+    best
 }
 
-fn update_is_valve(coord: CoordType, is_valve: bool) -> CoordType {
-    CoordType {
-        letter1: coord.letter1,
-        letter2: coord.letter2,
-        is_valve
+// "You" and "The elephant" like the puzzle, and then a herd of numbered elephants
+fn agent_name(agent: usize) -> String {
+    match agent {
+        0 => "You".to_string(),
+        1 => "The elephant".to_string(),
+        _ => format!("Elephant {}", agent),
     }
 }
 
-fn calc_distances(paths: &HashMap<CoordType, HashMap<CoordType, CoordType>>) -> HashMap<(CoordType, CoordType), i64> {
-    let mut distances: HashMap<(CoordType, CoordType), i64> = HashMap::new();
-
-    for prev_valve in paths.keys() {
-        for next_valve in paths.keys() {
-            if next_valve == prev_valve {
-                continue;
-            }
-            let path = reconstruct_path(&paths[&prev_valve], *next_valve);
-            distances.insert((*prev_valve, *next_valve), path.len() as i64);
-        }
+// Like "Valves BB, DD, and JJ are open" as in the puzzle
+fn describe_open(names: &[&str]) -> String {
+    match names {
+        [] => "No valves are open".to_string(),
+        [name] => format!("Valve {} is open", name),
+        [first, second] => format!("Valves {} and {} are open", first, second),
+        [rest @ .., last] => format!("Valves {}, and {} are open", rest.join(", "), last),
     }
-
-    distances
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let valves = read_valves(file_path);
-    // let num_valves = valves.iter().filter(|valve| valve.flow_rate > 0).count();
-    let valves_map: HashMap<CoordType, Valve> = valves.into_iter().map(|valve| (valve.name, valve)).collect();
-
-    let start = to_valve_key("AA");
-    //let result = dijkstra(&valves_map, start);
-    let mut paths: HashMap<CoordType, HashMap<CoordType, CoordType>> = HashMap::new();
-
-    let valves_with_flow: Vec<Valve> = valves_map.values().filter_map(|valve| {
-        if valve.flow_rate > 0 {
-            Some(Valve {
-                name: update_is_valve(valve.name, true),
-                flow_rate: valve.flow_rate,
-                next_valves: valve.next_valves.clone(),
-            })
-        } else {
-            None
+// Plays each agent's route out minute by minute in the words of the puzzle's example, walking between valves
+// one tunnel at a time
+pub fn narrate(valves: &[Valve], graph: &ValveGraph, routes: &[Route], total_minutes: i64) -> String {
+    let distances = tunnel_distances(valves);
+    // where each agent is at the end of each minute, and whether it spent the minute opening the valve there
+    let steps: Vec<Vec<(ValveId, bool)>> = routes.iter().map(|route| {
+        let mut position = graph.valves[graph.start()];
+        let mut steps = Vec::new();
+        for (_minutes_left, idx) in route.iter() {
+            let valve = graph.valves[*idx];
+            while position != valve {
+                let walk = distances[position][valve].unwrap();
+                position = *valves[position].next_valves.iter().find(|next| distances[**next][valve] == Some(walk - 1)).unwrap();
+                steps.push((position, false));
+            }
+            steps.push((position, true));
         }
+        steps
     }).collect();
 
-    for valve_key in valves_map.keys() {
-        paths.insert(*valve_key, dijkstra(&valves_map, *valve_key));
-        let coord_type = CoordType {
-            letter1: valve_key.letter1,
-            letter2: valve_key.letter2,
-            is_valve: true
-        };
-        paths.insert(coord_type, dijkstra(&valves_map, coord_type));
-    }
-
-    let distances = calc_distances(&paths);
+    let mut open: Vec<ValveId> = Vec::new();
+    let mut released = 0;
+    let mut out = String::new();
+    for minute in 1..=total_minutes {
+        out.push_str(&format!("== Minute {} ==\n", minute));
+        let mut names: Vec<&str> = open.iter().map(|valve| valves[*valve].name.as_str()).collect();
+        names.sort();
+        let pressure: i64 = open.iter().map(|valve| valves[*valve].flow_rate).sum();
+        released += pressure;
+        match open.len() {
+            0 => out.push_str(&format!("{}.\n", describe_open(&names))),
+            _ => out.push_str(&format!("{}, releasing {} pressure ({} so far).\n", describe_open(&names), pressure, released)),
+        }
 
-    let best_flow = find_best_path(&valves_with_flow, &distances, start);
+        for (agent, steps) in steps.iter().enumerate() {
+            let (position, opening) = match steps.get(minute as usize - 1) {
+                Some(step) => *step,
+                None => continue,
+            };
+            let (open_verb, move_verb) = if agent == 0 { ("open", "move") } else { ("opens", "moves") };
+            if opening {
+                out.push_str(&format!("{} {} valve {}.\n", agent_name(agent), open_verb, valves[position].name));
+                open.push(position);
+            } else {
+                out.push_str(&format!("{} {} to valve {}.\n", agent_name(agent), move_verb, valves[position].name));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
 
-    //println!("best path {:?} {}", best_path, best_path_flow);
-    best_flow
+// How the agents get the answer, for the explain param
+fn explain(valves: &[Valve], graph: &ValveGraph, plan: &[u64], total_minutes: i64) -> String {
+    let routes: Vec<(i64, Route)> = plan.iter().map(|opened| best_route(graph, graph.start(), total_minutes, *opened)).collect();
+    let released: i64 = routes.iter().map(|(flow, _route)| flow).sum();
+    format!("{}Most pressure {} agent{} can release in {} minutes: {}\n\n",
+            narrate(valves, graph, &routes.iter().map(|(_flow, route)| route.clone()).collect::<Vec<_>>(), total_minutes),
+            plan.len(), if plan.len() == 1 { "" } else { "s" }, total_minutes, released)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub minutes: i64,
+    // minutes spent teaching the elephant before part 2 starts
+    pub training: i64,
+    // how many agents open valves at once, otherwise just you in part 1 and you and the elephant in part 2
+    pub agents: Option<usize>,
+    // print what each agent does minute by minute before the answer
+    pub explain: bool,
+}
 
-fn find_best_path_elephant(valves_with_flow: &Vec<Valve>, distances: &HashMap<(CoordType, CoordType), i64>, start: CoordType) -> i64 {
-    struct State {
-        path: Vec<CoordType>,
-        me_minutes: i64,
-        me_current: CoordType,
-        elephant_minutes: i64,
-        elephant_current: CoordType,
-        flow: i64
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            minutes: 30,
+            training: 4,
+            agents: None,
+            explain: false,
+        }
     }
+}
 
-    const TOTAL_MINUTES: i64 = 26;
-    let mut old_moves: Vec<State> = vec![State {
-        path: vec![start],
-        me_minutes: 0,
-        me_current: start,
-        elephant_minutes: 0,
-        elephant_current: start,
-        flow: 0
-    }];
-    let mut max_flow = 0;
-
-    println!("distances {} {:?}", distances.len(), distances);
-    println!("valves {} {:?}", valves_with_flow.len(), valves_with_flow);
-    println!("start {:?}", start);
-
-    loop {
-        if old_moves.is_empty() {
-            return max_flow;
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["minutes", "training", "agents", "explain"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "minutes" => self.minutes = parse_value::<NonZeroU32>(key, value, "a number of minutes from 1 up")?.get() as i64,
+            "training" => self.training = parse_value::<u32>(key, value, "a number of minutes")? as i64,
+            "agents" => self.agents = Some(parse_value::<NonZeroUsize>(key, value, "a number of agents from 1 up")?.get()),
+            "explain" => self.explain = parse_value(key, value, "true or false")?,
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
         }
-        println!("old_moves {}", old_moves.len());
-        let new_moves = old_moves.iter().filter_map(|old_state| {
+        Ok(())
+    }
+}
 
-            Some(valves_with_flow.iter().filter_map(|valve| {
-                if old_state.me_minutes >= TOTAL_MINUTES {
-                    return None;
-                }
-                if old_state.path.contains(&valve.name) {
-                    return None;
-                }
+pub struct Day16;
 
-                let minutes = old_state.me_minutes + distances[&(old_state.me_current, valve.name)];
-                let new_flow = (max((TOTAL_MINUTES - minutes), 0) * valve.flow_rate);
-                if new_flow == 0 {
-                    return None
-                }
-                let flow = new_flow + old_state.flow;
-                let mut new_move_path = old_state.path.clone();
-                new_move_path.push(valve.name);
-
-                Some(State {
-                    path: new_move_path,
-                    me_minutes: minutes,
-                    me_current: valve.name,
-                    elephant_minutes: old_state.elephant_minutes,
-                    elephant_current: old_state.elephant_current,
-                    flow: flow
-                })
-            }).chain(valves_with_flow.iter().filter_map(|valve| {
-                if old_state.elephant_minutes >= TOTAL_MINUTES {
-                    return None;
-                }
-                if old_state.path.contains(&valve.name) {
-                    return None;
-                }
-
-                let minutes = old_state.elephant_minutes + distances[&(old_state.elephant_current, valve.name)];
-                let new_flow = (max((TOTAL_MINUTES - minutes), 0) * valve.flow_rate);
-                if new_flow == 0 {
-                    return None
-                }
-                let flow = new_flow + old_state.flow;
-                let mut new_move_path = old_state.path.clone();
-                new_move_path.push(valve.name);
-                Some(State {
-                    path: new_move_path,
-                    me_minutes: old_state.me_minutes,
-                    me_current: old_state.me_current,
-                    elephant_minutes: minutes,
-                    elephant_current: valve.name,
-                    flow: flow
-                })
-
-            })))
-        }).flatten();
-
-        /*
-        for new_move in new_moves.iter() {
-            if new_move.me_minutes >= TOTAL_MINUTES && new_move.elephant_minutes >= TOTAL_MINUTES {
-                if new_move.flow > max_flow {
-                    //max_flow = new_move.flow;
-                }
-            }
-        }*/
-
-        old_moves = new_moves.into_iter().filter(|new_move| {
-            if new_move.flow > max_flow {
-                max_flow = new_move.flow;
-            }
-            if (new_move.me_minutes >= TOTAL_MINUTES && new_move.elephant_minutes >= TOTAL_MINUTES) {
-                return false;
-            }
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+    type Input = Vec<Valve>;
+    type Config = Config;
 
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_valves(contents)
+    }
 
-            let max_remaining_flow_rate = {
-                valves_with_flow.iter().filter_map(|other_valve| {
-                    if new_move.path.contains(&other_valve.name) {
-                        None
-                    } else {
-                        Some(
-                            max(max(
-                                (TOTAL_MINUTES - new_move.me_minutes - distances[&(new_move.me_current, other_valve.name)]) * other_valve.flow_rate,
-                                (TOTAL_MINUTES - new_move.elephant_minutes - distances[&(new_move.elephant_current, other_valve.name)]) * other_valve.flow_rate,
-                            ), 0)
-                        )
-                    }
-                }).max()
-            };
+    fn part1(valves: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(explained(part1_solve(valves, config)))
+    }
 
-            match max_remaining_flow_rate {
-                Some(_max_remaining_flow) => {
-                    if max_flow > (_max_remaining_flow + new_move.flow) {
-                        return false;
-                    }
-                }
-                _ => {}
-            }
+    fn part2(valves: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(explained(part2_solve(valves, config)))
+    }
+}
 
-            return true;
-        }
-        ).collect();
+fn explained((released, explanation): (i64, Option<String>)) -> Answer {
+    match explanation {
+        Some(explanation) => Answer::from(released).explained(explanation),
+        None => released.into(),
     }
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let valves = read_valves(file_path);
-    // let num_valves = valves.iter().filter(|valve| valve.flow_rate > 0).count();
-    let valves_map: HashMap<CoordType, Valve> = valves.into_iter().map(|valve| (valve.name, valve)).collect();
-
-    let start = to_valve_key("AA");
-    //let result = dijkstra(&valves_map, start);
-    let mut paths: HashMap<CoordType, HashMap<CoordType, CoordType>> = HashMap::new();
-
-    let valves_with_flow: Vec<Valve> = valves_map.values().filter_map(|valve| {
-        if valve.flow_rate > 0 {
-            Some(Valve {
-                name: update_is_valve(valve.name, true),
-                flow_rate: valve.flow_rate,
-                next_valves: valve.next_valves.clone(),
-            })
-        } else {
-            None
-        }
-    }).collect();
+// the most pressure released, and how if the explain param is on
+fn solve(valves: &[Valve], config: &Config, agents: usize, total_minutes: i64) -> (i64, Option<String>) {
+    let graph = ValveGraph::new(valves, start(valves));
+    let flows = best_flows(&graph, total_minutes);
+    let plan = best_team(&flows, agents);
+    let explanation = if config.explain { Some(explain(valves, &graph, &plan, total_minutes)) } else { None };
+    (plan.iter().map(|opened| flows[opened]).sum(), explanation)
+}
 
-    for valve_key in valves_map.keys() {
-        paths.insert(*valve_key, dijkstra(&valves_map, *valve_key));
-        let coord_type = CoordType {
-            letter1: valve_key.letter1,
-            letter2: valve_key.letter2,
-            is_valve: true
-        };
-        paths.insert(coord_type, dijkstra(&valves_map, coord_type));
-    }
+fn part1_solve(valves: &[Valve], config: &Config) -> (i64, Option<String>) {
+    solve(valves, config, config.agents.unwrap_or(1), config.minutes)
+}
+
+fn part2_solve(valves: &[Valve], config: &Config) -> (i64, Option<String>) {
+    // training for longer than there is leaves no time at all
+    solve(valves, config, config.agents.unwrap_or(2), (config.minutes - config.training).max(0))
+}
 
-    let distances = calc_distances(&paths);
+pub fn part1(valves: &[Valve], config: &Config) -> i64 {
+    part1_solve(valves, config).0
+}
 
-    let best_flow = find_best_path_elephant(&valves_with_flow, &distances, start);
+pub fn part2(valves: &[Valve], config: &Config) -> i64 {
+    part2_solve(valves, config).0
+}
 
-    //println!("best path {:?} {}", best_path, best_path_flow);
-    best_flow
-}
\ No newline at end of file
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB\n",
+        "Valve BB has flow rate=13; tunnels lead to valves CC, AA\n",
+        "Valve CC has flow rate=2; tunnels lead to valves DD, BB\n",
+        "Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE\n",
+        "Valve EE has flow rate=3; tunnels lead to valves FF, DD\n",
+        "Valve FF has flow rate=0; tunnels lead to valves EE, GG\n",
+        "Valve GG has flow rate=0; tunnels lead to valves FF, HH\n",
+        "Valve HH has flow rate=22; tunnel leads to valve GG\n",
+        "Valve II has flow rate=0; tunnels lead to valves AA, JJ\n",
+        "Valve JJ has flow rate=21; tunnel leads to valve II\n",
+    );
+    let valves = Day16::parse(contents).unwrap();
+    let too_many: String = (0..65u8).map(|idx| format!("Valve {}{} has flow rate=1; tunnel leads to valve AA\n", (b'A' + idx / 26) as char, (b'A' + idx % 26) as char)).collect();
+    assert_eq!(Day16::parse(&format!("{}Valve BB has flow rate=1; tunnel leads to valve AA\n", contents)).unwrap_err().to_string(), "line 11, column 7: expected a valve which isn't listed already, found \"BB\"");
+    assert_eq!(Day16::parse(&too_many).unwrap_err().to_string(), "line 65, column 24: expected a flow rate of 0, at most 64 valves can have flow, found \"1\"");
+    let graph = ValveGraph::new(&valves, start(&valves));
+    assert_eq!(graph.valves, vec![1, 2, 3, 4, 7, 9, 0]);
+    assert_eq!(graph.distances[graph.start()], vec![Some(1), Some(2), Some(1), Some(2), Some(5), Some(2), Some(0)]);
+
+    let config = Config::default();
+    assert_eq!(part1(&valves, &config), 1651);
+    assert_eq!(part2(&valves, &config), 1707);
+
+    // with more agents than valves worth opening, everyone walks straight to one
+    let mut crowd = Config { minutes: 22, agents: Some(7), ..config };
+    assert_eq!(part1(&valves, &crowd), 20 * 20 + 13 * 20 + 2 * 19 + 3 * 19 + 21 * 19 + 22 * 16);
+    assert_eq!(part2(&valves, &Config { agents: Some(1), ..config }), part1(&valves, &Config { minutes: 26, ..config }));
+    assert!(params::Config::set(&mut crowd, "agents", "0").is_err());
+    assert!(params::Config::set(&mut crowd, "minutes", "-9").is_err());
+    assert_eq!(part2(&valves, &Config { minutes: 3, ..config }), 0);
+
+    // the same schedule as the puzzle's example
+    let flows = best_flows(&graph, 30);
+    let route = best_route(&graph, graph.start(), 30, best_team(&flows, 1)[0]).1;
+    assert_eq!(route.iter().map(|(_minutes_left, idx)| valves[graph.valves[*idx]].name.as_str()).collect::<Vec<_>>(), vec!["DD", "BB", "JJ", "HH", "EE", "CC"]);
+    let story = narrate(&valves, &graph, &[route], 30);
+    assert!(story.starts_with("== Minute 1 ==\nNo valves are open.\nYou move to valve DD.\n\n== Minute 2 ==\nNo valves are open.\nYou open valve DD.\n\n"));
+    assert!(story.contains("== Minute 6 ==\nValves BB and DD are open, releasing 33 pressure (93 so far).\nYou move to valve AA.\n"));
+    assert!(story.ends_with("== Minute 30 ==\nValves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure (1651 so far).\n\n"));
+}
@@ -1,25 +1,24 @@
-use std::collections::{HashMap, HashSet};
-use crate::common::get_trimmed_lines;
-use array2d::Array2D;
-use itertools::any;
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use crate::animate::{no_frames, Frame, OnFrame};
+use crate::common::{get_trimmed_lines, Grid};
+use crate::error::ParseError;
+use crate::params::{self, parse_value, ParamError};
 use lazy_static::lazy_static;
+use crate::solution::{Answer, Part, Solution};
 
 const NUM_COLS: usize = 7;
-type Row = [char; NUM_COLS];
 
 const NUM_ROWS_RECORD: u64 = 24;
 
+// row 0 is the floor and rows count upwards
 struct Board {
-    rows: Vec<Row>,
+    rows: Grid<char>,
     above_last_occupied_row: u64,
     active_piece: Option<((u64, u64), u64)>,
     piece_num: u64,
 }
 
-fn empty_row() -> Row {
-    ['.', '.', '.', '.', '.', '.', '.']
-}
-
 fn make_pieces() -> Vec<Vec<(u64, u64)>> {
     vec![
         vec![(0, 0), (0, 1), (0, 2), (0, 3)],
@@ -36,20 +35,14 @@ lazy_static! {
 
 impl Board {
     pub fn set(self: &mut Board, row: u64, col: u64, val: char) {
-        if row >= self.rows.len() as u64 {
-            for _ in self.rows.len()..row as usize + 1 {
-                self.rows.push(empty_row())
-            }
-        }
-
-        self.rows[row as usize][col as usize] = val;
+        self.rows.set((row as i64, col as i64), val);
         if val != '.' {
             if row >= self.above_last_occupied_row {
                 self.above_last_occupied_row = row + 1;
             }
         } else {
-            for _row in (0..self.rows.len()).rev() {
-                if any(self.rows[_row], |x| x != '.') {
+            for _row in (0..self.rows.num_rows() as i64).rev() {
+                if self.rows.row(_row).unwrap().iter().any(|x| *x != '.') {
                     self.above_last_occupied_row = (_row as u64) + 1;
                     break;
                 }
@@ -57,35 +50,42 @@ impl Board {
         }
     }
 
-    pub fn get(self: &Board, row: u64, col: u64) -> char {
-        self.rows[row as usize][col as usize]
+    pub fn get(self: &Board, row: i64, col: i64) -> char {
+        self.rows[(row, col)]
     }
 
     pub fn new() -> Board {
         Board {
             above_last_occupied_row: 0,
-            rows: Vec::new(),
+            rows: Grid::filled_with('.', (0, 0), (0, NUM_COLS as i64 - 1)),
             active_piece: None,
             piece_num: 0,
         }
 
     }
 
-    fn print(self: &Board) {
-        for row in self.rows.iter().rev() {
-            print!("|");
-            for col in 0..NUM_COLS {
-                let c = row[col];
-                print!("{}", c);
-            }
-            println!("|");
+    // Upside down compared to rows so the floor is at the bottom, with rows as negative numbers
+    fn frame(self: &Board, rocks: u64) -> Frame {
+        let top = self.rows.num_rows() as i64 - 1;
+        let mut grid = Grid::filled_with('|', (-top, -1), (1, NUM_COLS as i64));
+        for (row, col) in self.rows.coords() {
+            grid[(-row, col)] = self.rows[(row, col)];
         }
-
-        print!("|");
-        for _ in 0..NUM_COLS {
-            print!("=");
+        for col in 0..NUM_COLS as i64 {
+            grid[(1, col)] = '-';
+        }
+        grid[(1, -1)] = '+';
+        grid[(1, NUM_COLS as i64)] = '+';
+
+        let focus = match self.active_piece {
+            Some(((row, col), _piece)) => (-(row as i64), col as i64),
+            None => (-(self.above_last_occupied_row as i64), 0),
+        };
+        Frame {
+            grid,
+            focus: Some(focus),
+            caption: format!("{} rocks, {} units tall", rocks, self.above_last_occupied_row),
         }
-        println!("|");
     }
 
     fn push_active_piece(self: &mut Board, direction: i64) -> bool {
@@ -103,7 +103,7 @@ impl Board {
                     for (row, col) in PIECES[_active_piece.1 as usize].iter() {
                         let new_col = _active_piece.0.1 as i64 + *col as i64 + direction;
                         let new_row = _active_piece.0.0 as i64 + *row as i64;
-                        if new_col < 0 || new_col >= NUM_COLS as i64 || self.rows[new_row as usize][new_col as usize] == '#' {
+                        if new_col < 0 || new_col >= NUM_COLS as i64 || self.get(new_row, new_col) == '#' {
                             ret = false;
                             break;
                         }
@@ -132,7 +132,7 @@ impl Board {
                     for (row, col) in PIECES[_active_piece.1 as usize].iter() {
                         let new_row = _active_piece.0.0 as i64 + *row as i64 - 1;
                         let new_col = _active_piece.0.1 as i64 + *col as i64 + moved_col;
-                        if new_row < 0 || self.rows[new_row as usize][new_col as usize] == '#' {
+                        if new_row < 0 || self.get(new_row, new_col) == '#' {
                             ret = false;
                             break;
                         }
@@ -200,16 +200,81 @@ impl Board {
     }
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let lines = get_trimmed_lines(&file_path);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub part1_rocks: u64,
+    pub part2_rocks: u64,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            part1_rocks: 2022,
+            part2_rocks: 1000000000000,
+        }
+    }
+}
+
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["part1_rocks", "part2_rocks"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "part1_rocks" => self.part1_rocks = parse_value::<NonZeroU64>(key, value, "a number of rocks from 1 up")?.get(),
+            "part2_rocks" => self.part2_rocks = parse_value::<NonZeroU64>(key, value, "a number of rocks from 1 up")?.get(),
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
+        }
+        Ok(())
+    }
+}
+
+pub struct Day17;
 
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+    const ANIMATED: bool = true;
+    type Input = Vec<char>;
+    type Config = Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+        let line = lines.first().ok_or_else(|| ParseError::new(1, 1, "", "a line of jet directions"))?;
+        if let Some(idx) = line.find(|c| c != '<' && c != '>') {
+            return Err(line.error_at(&line[idx..], "a jet direction < or >"));
+        }
+        if let Some(extra) = lines.get(1) {
+            return Err(extra.error("a single line of jet directions"));
+        }
+        Ok(line.chars().collect())
+    }
+
+    fn part1(directions: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(directions, config.part1_rocks).into())
+    }
+
+    fn part2(directions: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(directions, config.part2_rocks).into())
+    }
+
+    fn animate(directions: &Self::Input, config: &Self::Config, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(match part {
+            Part::One => drop_rocks(directions, config.part1_rocks, on_frame),
+            Part::Two => drop_rocks_repeating(directions, config.part2_rocks, on_frame),
+        }.into()))
+    }
+}
+
+pub fn part1(directions: &[char], total_rocks: u64) -> i64 {
+    drop_rocks(directions, total_rocks, &mut no_frames)
+}
+
+fn drop_rocks(directions: &[char], total_rocks: u64, on_frame: &mut OnFrame) -> i64 {
     let mut board = Board::new();
-    let directions: Vec<char> = lines.first().unwrap().chars().collect();
     let mut direction_idx = 0;
     let mut count = 0;
 
     loop {
-        if count == 2022 {
+        if count == total_rocks {
             break;
         }
 
@@ -219,6 +284,7 @@ pub fn part1(file_path: &str) -> i64 {
         if board.simulate_rock(direction) {
             count += 1;
         }
+        on_frame(&|| board.frame(count));
 
         direction_idx = (direction_idx + 1) % directions.len();
     }
@@ -226,54 +292,38 @@ pub fn part1(file_path: &str) -> i64 {
     board.above_last_occupied_row as i64
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let lines = get_trimmed_lines(&file_path);
+pub fn part2(directions: &[char], total_rocks: u64) -> i64 {
+    drop_rocks_repeating(directions, total_rocks, &mut no_frames)
+}
 
+fn drop_rocks_repeating(directions: &[char], total_rocks: u64, on_frame: &mut OnFrame) -> i64 {
     let mut board = Board::new();
 
-    let directions: Vec<char> = lines.first().unwrap().chars().collect();
     let mut direction_idx = 0;
     let mut count: u64 = 0;
-    let mut repeat: HashMap<([[char; 7]; NUM_ROWS_RECORD as usize], u64), (u64, u64, u64)> = HashMap::new();
+    let mut repeat: HashMap<(Vec<char>, u64), (u64, u64, u64)> = HashMap::new();
 
     loop {
         let direction = directions[direction_idx];
         //println!("count = {}, direction = {}, height = {}", count, direction, board.above_last_occupied_row);
 
         let new_piece = board.simulate_rock(direction);
+        on_frame(&|| board.frame(count + new_piece as u64));
         if new_piece {
             count += 1;
+            // too few rocks for the tower to start repeating
+            if count == total_rocks {
+                return board.above_last_occupied_row as i64;
+            }
         }
 
         direction_idx = (direction_idx + 1) % directions.len();
 
-        if board.above_last_occupied_row >= 24 {
-            let key = ([
-                board.rows[(board.above_last_occupied_row - 1) as usize],
-                board.rows[(board.above_last_occupied_row - 2) as usize],
-                board.rows[(board.above_last_occupied_row - 3) as usize],
-                board.rows[(board.above_last_occupied_row - 4) as usize],
-                board.rows[(board.above_last_occupied_row - 5) as usize],
-                board.rows[(board.above_last_occupied_row - 6) as usize],
-                board.rows[(board.above_last_occupied_row - 7) as usize],
-                board.rows[(board.above_last_occupied_row - 8) as usize],
-                board.rows[(board.above_last_occupied_row - 9) as usize],
-                board.rows[(board.above_last_occupied_row - 10) as usize],
-                board.rows[(board.above_last_occupied_row - 11) as usize],
-                board.rows[(board.above_last_occupied_row - 12) as usize],
-               board.rows[(board.above_last_occupied_row - 13) as usize],
-               board.rows[(board.above_last_occupied_row - 14) as usize],
-               board.rows[(board.above_last_occupied_row - 15) as usize],
-               board.rows[(board.above_last_occupied_row - 16) as usize],
-               board.rows[(board.above_last_occupied_row - 17) as usize],
-               board.rows[(board.above_last_occupied_row - 18) as usize],
-               board.rows[(board.above_last_occupied_row - 19) as usize],
-               board.rows[(board.above_last_occupied_row - 20) as usize],
-               board.rows[(board.above_last_occupied_row - 21) as usize],
-               board.rows[(board.above_last_occupied_row - 22) as usize],
-               board.rows[(board.above_last_occupied_row - 23) as usize],
-               board.rows[(board.above_last_occupied_row - 24) as usize],
-            ], board.piece_num);
+        if board.above_last_occupied_row >= NUM_ROWS_RECORD {
+            // the top rows of the tower, which is all a falling rock can reach
+            let key: (Vec<char>, u64) = ((1..=NUM_ROWS_RECORD).flat_map(|depth| {
+                board.rows.row((board.above_last_occupied_row - depth) as i64).unwrap().to_vec()
+            }).collect(), board.piece_num);
             //println!("{:?}", repeat.len());
 
             if new_piece {
@@ -282,11 +332,10 @@ pub fn part2(file_path: &str) -> i64 {
                     Some((_count, _above_last_occupied_row, _last_piece_num)) => {
                         let row_diff = board.above_last_occupied_row - _above_last_occupied_row;
                         let rocks_diff = count - _count;
-                        const TOTAL_ROCKS: u64 = 1000000000000;
-                        let rounds = (TOTAL_ROCKS - _count) / rocks_diff;
+                        let rounds = (total_rocks - _count) / rocks_diff;
 
                         let mut updated_rocks_count = _count + (rocks_diff * rounds);
-                        while updated_rocks_count < TOTAL_ROCKS {
+                        while updated_rocks_count < total_rocks {
                             if board.simulate_rock(directions[direction_idx]) {
                                 updated_rocks_count += 1;
                             }
@@ -302,4 +351,15 @@ pub fn part2(file_path: &str) -> i64 {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>\n";
+    let directions = Day17::parse(contents).unwrap();
+    let config = Config::default();
+    assert_eq!(part1(&directions, config.part1_rocks), 3068);
+    assert_eq!(part2(&directions, config.part2_rocks), 1514285714288);
+    // skipping ahead by whole cycles has to agree with simulating every rock
+    assert_eq!(part2(&directions, config.part1_rocks), 3068);
+}
@@ -1,11 +1,13 @@
 use std::collections::HashSet;
 use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-struct Coord {
-    x: i64,
-    y: i64,
-    z: i64,
+pub struct Coord {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -15,20 +17,45 @@ struct Adjustment {
     z: i64,
 }
 
-fn read_tuples(file_path: &str) -> Vec<Coord> {
-    get_trimmed_lines(file_path).iter().map(|line| {
-        let mut split = line.split(",");
-        let a: i64 = split.next().unwrap().parse().unwrap();
-        let b: i64 = split.next().unwrap().parse().unwrap();
-        let c: i64 = split.next().unwrap().parse().unwrap();
-        Coord { x:a, y:b, z:c }
+pub fn read_tuples(contents: &str) -> Result<Vec<Coord>, ParseError> {
+    let lines = get_trimmed_lines(contents);
+    if lines.is_empty() {
+        return Err(ParseError::new(1, 1, "", "a cube like 2,2,2"));
+    }
+    lines.iter().map(|line| {
+        match line.split(',').collect::<Vec<&str>>()[..] {
+            [a, b, c] => Ok(Coord {
+                x: line.parse_at(a, "an x coordinate")?,
+                y: line.parse_at(b, "a y coordinate")?,
+                z: line.parse_at(c, "a z coordinate")?,
+            }),
+            _ => Err(line.error("a cube like 2,2,2"))
+        }
     }).collect()
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let tuples = read_tuples(file_path);
+pub struct Day18;
 
-    let s: HashSet<Coord> = HashSet::from_iter(tuples.iter().map(|t| *t));
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+    type Input = Vec<Coord>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_tuples(contents)
+    }
+
+    fn part1(tuples: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(tuples).into())
+    }
+
+    fn part2(tuples: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(tuples).into())
+    }
+}
+
+pub fn part1(tuples: &[Coord]) -> i64 {
+    let s: HashSet<Coord> = HashSet::from_iter(tuples.iter().copied());
 
     let mut count = 0;
     for tup in s.iter() {
@@ -102,13 +129,11 @@ fn _touching_outside(coords_outside: &mut HashSet<Coord>, cubes: &HashSet<Coord>
 }
 
 fn touching_outside(coords_outside: &mut HashSet<Coord>, cubes: &HashSet<Coord>, coord: Coord, bounds: &[(i64, i64, i64); 2]) -> bool {
-    _touching_outside(coords_outside, &cubes, coord, bounds, &mut HashSet::new())
+    _touching_outside(coords_outside, cubes, coord, bounds, &mut HashSet::new())
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let tuples = read_tuples(file_path);
-
-    let cubes: HashSet<Coord> = HashSet::from_iter(tuples.iter().map(|t| *t));
+pub fn part2(tuples: &[Coord]) -> i64 {
+    let cubes: HashSet<Coord> = HashSet::from_iter(tuples.iter().copied());
 
     let bounds = [
         (
@@ -136,14 +161,35 @@ pub fn part2(file_path: &str) -> i64 {
             Adjustment {x:0, y:0, z:1}
         ] {
             let adjusted = add(*tup, adjustment);
-            if !cubes.contains(&adjusted) {
-                if touching_outside(&mut coords_outside, &cubes, adjusted, &bounds) {
+            if !cubes.contains(&adjusted)
+                && touching_outside(&mut coords_outside, &cubes, adjusted, &bounds) {
                     count += 1;
                 }
-            }
         }
     }
 
     // 686 is too low
     count
 }
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "2,2,2\n",
+        "1,2,2\n",
+        "3,2,2\n",
+        "2,1,2\n",
+        "2,3,2\n",
+        "2,2,1\n",
+        "2,2,3\n",
+        "2,2,4\n",
+        "2,2,6\n",
+        "1,2,5\n",
+        "3,2,5\n",
+        "2,1,5\n",
+        "2,3,5\n",
+    );
+    let tuples = Day18::parse(contents).unwrap();
+    assert_eq!(part1(&tuples), 64);
+    assert_eq!(part2(&tuples), 58);
+}
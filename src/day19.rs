@@ -1,252 +1,438 @@
 use std::cmp::max;
-use std::collections::HashSet;
-use std::fs::read;
-use std::iter::{empty, once, zip};
-use std::path::Iter;
 use lazy_static::lazy_static;
+use log::{debug, trace};
 use regex::Regex;
-use crate::common::get_trimmed_lines;
-use std::thread;
-use std::thread::JoinHandle;
-use itertools::chain;
+use crate::common::{default_jobs, get_trimmed_lines, parallel_map, Line};
+use crate::error::ParseError;
+use crate::params::{self, parse_value, ParamError};
+use crate::solution::{Answer, Solution};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct OreRobot {
-    ore: u64,
+// A robot collecting one of the blueprint's resources every minute
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Robot {
+    pub collects: usize,
+    // how much of each resource it takes to build, indexed like Blueprint::resources
+    pub cost: Vec<u64>,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct ClayRobot {
-    ore: u64,
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Blueprint {
+    pub number: u64,
+    // every resource in the order it's first mentioned, which is ore, clay, obsidian and geode for the puzzle
+    pub resources: Vec<String>,
+    pub robots: Vec<Robot>,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct ObsidianRobot {
-    ore: u64,
-    clay: u64,
+impl Blueprint {
+    pub fn resource(&self, name: &str) -> Option<usize> {
+        self.resources.iter().position(|resource| resource == name)
+    }
+
+    fn add_resource(&mut self, name: &str) -> usize {
+        match self.resource(name) {
+            Some(idx) => idx,
+            None => {
+                self.resources.push(name.to_string());
+                for robot in self.robots.iter_mut() {
+                    robot.cost.push(0);
+                }
+                self.resources.len() - 1
+            }
+        }
+    }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct GeodeRobot {
-    ore: u64,
-    obsidian: u64,
+lazy_static! {
+    static ref BLUEPRINT_RE: Regex = Regex::new(r"^Blueprint (\d+):").unwrap();
+    static ref ROBOT_RE: Regex = Regex::new(r"^[Ee]ach (\w+) robot costs (.+)$").unwrap();
+    static ref COST_RE: Regex = Regex::new(r"^(\d+) (\w+)$").unwrap();
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Blueprint {
-    number: u64,
-    ore: OreRobot,
-    clay: ClayRobot,
-    obsidian: ObsidianRobot,
-    geode: GeodeRobot,
+const ROBOT_EXPECTED: &str = "a robot like \"Each obsidian robot costs 3 ore and 14 clay.\"";
+
+// Adds the robots from sentences like "Each geode robot costs 2 ore and 7 obsidian." in text, part of line
+fn read_robots(blueprint: &mut Blueprint, line: &Line, text: &str) -> Result<(), ParseError> {
+    for sentence in text.split('.').map(|sentence| sentence.trim()).filter(|sentence| !sentence.is_empty()) {
+        let cap = ROBOT_RE.captures(sentence).ok_or_else(|| line.error_at(sentence, ROBOT_EXPECTED))?;
+        let collects = blueprint.add_resource(cap.get(1).unwrap().as_str());
+
+        // costs are listed like "2 ore", "3 ore and 14 clay" or "1 ore, 2 clay and 3 obsidian"
+        let mut cost = vec![0; blueprint.resources.len()];
+        for item in cap.get(2).unwrap().as_str().split(" and ").flat_map(|items| items.split(',')).map(|item| item.trim()) {
+            let cost_cap = COST_RE.captures(item).ok_or_else(|| line.error_at(item, "a cost like \"3 ore\""))?;
+            let amount: u64 = line.parse_at(cost_cap.get(1).unwrap().as_str(), "a number")?;
+            let resource = blueprint.add_resource(cost_cap.get(2).unwrap().as_str());
+            cost.resize(blueprint.resources.len(), 0);
+            cost[resource] += amount;
+        }
+        blueprint.robots.push(Robot { collects, cost });
+    }
+    Ok(())
 }
 
-lazy_static! {
-    static ref RE: Regex = Regex::new(
-        concat!(
-            r"Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. ",
-            r"Each obsidian robot costs (\d+) ore and (\d+) clay. ",
-            r"Each geode robot costs (\d+) ore and (\d+) obsidian."
-        )
-    ).unwrap();
-}
-
-fn read_blueprints(file_path: &str) -> Vec<Blueprint> {
-    let lines = get_trimmed_lines(file_path);
-    lines.iter().map(|line| {
-        match RE.captures(line) {
+// Blueprints can be on one line each like the real input, or spread over several lines like the example
+pub fn read_blueprints(contents: &str) -> Result<Vec<Blueprint>, ParseError> {
+    let mut blueprints: Vec<(Line, Blueprint)> = Vec::new();
+    for line in get_trimmed_lines(contents) {
+        let text = match BLUEPRINT_RE.captures(&line) {
             Some(cap) => {
-                Blueprint {
-                    number: cap.get(1).unwrap().as_str().parse().unwrap(),
-                    ore: OreRobot { ore: cap.get(2).unwrap().as_str().parse().unwrap() },
-                    clay: ClayRobot { ore: cap.get(3).unwrap().as_str().parse().unwrap() },
-                    obsidian: ObsidianRobot {
-                        ore: cap.get(4).unwrap().as_str().parse().unwrap(),
-                        clay: cap.get(5).unwrap().as_str().parse().unwrap(),
-                    },
-                    geode: GeodeRobot {
-                        ore: cap.get(6).unwrap().as_str().parse().unwrap(),
-                        obsidian: cap.get(7).unwrap().as_str().parse().unwrap(),
-                    }
-                }
+                let number = line.parse_at(cap.get(1).unwrap().as_str(), "a blueprint number")?;
+                blueprints.push((line.clone(), Blueprint { number, resources: Vec::new(), robots: Vec::new() }));
+                &line[cap.get(0).unwrap().end()..]
             },
-            None => {
-                panic!("unexpected regex mismatch")
-            }
+            None if blueprints.is_empty() => return Err(line.error("a blueprint like \"Blueprint 1: Each ore robot costs 4 ore. ...\"")),
+            None => &line[..],
+        };
+        let (_start, blueprint) = blueprints.last_mut().unwrap();
+        read_robots(blueprint, &line, text)?;
+    }
+
+    blueprints.into_iter().map(|(start, blueprint)| {
+        if blueprint.robots.is_empty() {
+            return Err(start.error(ROBOT_EXPECTED));
         }
+        Ok(blueprint)
     }).collect()
 }
 
-#[derive(Copy, Debug, Clone, Eq, PartialEq, Hash)]
+// How many robots collecting each resource there are and how much of it has been collected
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Resources {
-    num_ore_robots: u64,
-    num_clay_robots: u64,
-    num_obsidian_robots: u64,
-    num_geode_robots: u64,
-
-    num_ore: u64,
-    num_clay: u64,
-    num_obsidian: u64,
-    num_geode: u64,
-}
-
-fn _calc_blueprint(blueprint: Blueprint, start: &Resources, total_minutes: u64) -> (Blueprint, u64) {
-    let mut minute = 0;
-    let mut choices = HashSet::from([*start]);
-    loop {
-        println!("blueprint: {}, minute: {}, choices: {}", blueprint.number, minute, choices.len());
-        if minute >= total_minutes {
-            return (blueprint, choices.iter().max_by_key(|resources| resources.num_geode).unwrap().num_geode);
+    robots: Vec<u64>,
+    amounts: Vec<u64>,
+}
+
+impl Resources {
+    // Collects for minutes and then builds robot
+    fn build(&mut self, robot: &Robot, minutes: u64) {
+        for (idx, amount) in self.amounts.iter_mut().enumerate() {
+            *amount = *amount + self.robots[idx] * minutes - robot.cost[idx];
         }
+        self.robots[robot.collects] += 1;
+    }
 
-        let mut new_choices = HashSet::new();
-
-        for resources in choices.iter() {
-            if resources.num_ore >= blueprint.geode.ore && resources.num_obsidian >= blueprint.geode.obsidian {
-                new_choices.insert(Resources {
-                    num_ore_robots: resources.num_ore_robots,
-                    num_clay_robots: resources.num_clay_robots,
-                    num_obsidian_robots: resources.num_obsidian_robots,
-                    num_geode_robots: resources.num_geode_robots + 1,
-                    num_ore: resources.num_ore + resources.num_ore_robots - blueprint.geode.ore,
-                    num_clay: resources.num_clay + resources.num_clay_robots,
-                    num_obsidian: resources.num_obsidian + resources.num_obsidian_robots - blueprint.geode.obsidian,
-                    num_geode: resources.num_geode + resources.num_geode_robots,
-                });
-                continue;
+    // Undoes build, so the search can backtrack without copying
+    fn unbuild(&mut self, robot: &Robot, minutes: u64) {
+        self.robots[robot.collects] -= 1;
+        for (idx, amount) in self.amounts.iter_mut().enumerate() {
+            *amount = *amount + robot.cost[idx] - self.robots[idx] * minutes;
+        }
+    }
+
+    // Minutes of collecting before there's enough to build robot, None if something it needs isn't being collected
+    fn minutes_to_afford(&self, robot: &Robot) -> Option<u64> {
+        robot.cost.iter().enumerate().try_fold(0, |wait, (idx, cost)| {
+            let have = self.amounts[idx];
+            if have >= *cost {
+                Some(wait)
+            } else if self.robots[idx] == 0 {
+                None
+            } else {
+                Some(max(wait, (cost - have).div_ceil(self.robots[idx])))
             }
+        })
+    }
+}
+
+// The robots to build to collect the most of the goal
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Plan {
+    pub collected: u64,
+    // the minute each robot is paid for, counting from 1 like the puzzle, and its index in Blueprint::robots
+    pub builds: Vec<(u64, usize)>,
+}
+
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    goal: usize,
+    minutes: u64,
+    // the most of each resource that can be spent in a minute. Only one robot gets built a minute, so robots
+    // beyond this would collect more than can ever be used.
+    max_spend: Vec<u64>,
+    // robots collecting the goal first since finding a good best early prunes more
+    order: Vec<usize>,
+    builds: Vec<(u64, usize)>,
+    best: Plan,
+}
+
+impl Search<'_> {
+    // Depth first over which robot to build next, skipping straight to the minute it's built. Branches which
+    // couldn't beat best even building a goal robot every minute left are dropped.
+    fn search(&mut self, resources: &mut Resources, minutes_left: u64) {
+        let goal = resources.amounts[self.goal] + resources.robots[self.goal] * minutes_left;
+        // building nothing more
+        if goal > self.best.collected {
+            self.best = Plan { collected: goal, builds: self.builds.clone() };
+        }
+        if goal + minutes_left * minutes_left.saturating_sub(1) / 2 <= self.best.collected {
+            return;
+        }
 
-            if resources.num_ore >= blueprint.obsidian.ore && resources.num_clay >= blueprint.obsidian.clay &&
-                resources.num_obsidian_robots < blueprint.geode.obsidian {
-                new_choices.insert(Resources {
-                    num_ore_robots: resources.num_ore_robots,
-                    num_clay_robots: resources.num_clay_robots,
-                    num_obsidian_robots: resources.num_obsidian_robots + 1,
-                    num_geode_robots: resources.num_geode_robots,
-                    num_ore: resources.num_ore + resources.num_ore_robots - blueprint.obsidian.ore,
-                    num_clay: resources.num_clay + resources.num_clay_robots - blueprint.obsidian.clay,
-                    num_obsidian: resources.num_obsidian + resources.num_obsidian_robots,
-                    num_geode: resources.num_geode + resources.num_geode_robots,
-                });
+        for idx in 0..self.order.len() {
+            let robot = &self.blueprint.robots[self.order[idx]];
+            if robot.collects != self.goal && resources.robots[robot.collects] >= self.max_spend[robot.collects] {
+                continue;
             }
+            // the robot has to be ready with at least a minute left for it to collect anything
+            let minutes = match resources.minutes_to_afford(robot) {
+                Some(wait) if wait + 1 < minutes_left => wait + 1,
+                _ => continue,
+            };
+            self.builds.push((self.minutes - minutes_left + minutes, self.order[idx]));
+            resources.build(robot, minutes);
+            self.search(resources, minutes_left - minutes);
+            resources.unbuild(robot, minutes);
+            self.builds.pop();
+        }
+    }
+}
+
+// The robots to build to collect the most of the goal resource in minutes, starting with one robot collecting start
+pub fn best_plan(blueprint: &Blueprint, config: &Config, minutes: u64) -> Plan {
+    let (goal, start) = match (blueprint.resource(&config.goal), blueprint.resource(&config.start)) {
+        (Some(goal), Some(start)) => (goal, start),
+        // nothing collects the goal, or nothing can get going
+        _ => return Plan::default(),
+    };
+
+    let num_resources = blueprint.resources.len();
+    let max_spend = (0..num_resources).map(|idx| blueprint.robots.iter().map(|robot| robot.cost[idx]).max().unwrap_or(0)).collect();
+    let mut order: Vec<usize> = (0..blueprint.robots.len()).rev().collect();
+    order.sort_by_key(|idx| blueprint.robots[*idx].collects != goal);
+
+    let mut resources = starting_resources(blueprint, start);
+    let mut search = Search { blueprint, goal, minutes, max_spend, order, builds: Vec::new(), best: Plan::default() };
+    search.search(&mut resources, minutes);
+    trace!("blueprint {} collects {} {} in {} minutes", blueprint.number, search.best.collected, config.goal, minutes);
+    search.best
+}
+
+pub fn calc_blueprint(blueprint: &Blueprint, config: &Config, minutes: u64) -> u64 {
+    best_plan(blueprint, config, minutes).collected
+}
+
+fn starting_resources(blueprint: &Blueprint, start: usize) -> Resources {
+    let mut robots = vec![0; blueprint.resources.len()];
+    robots[start] = 1;
+    Resources { robots, amounts: vec![0; blueprint.resources.len()] }
+}
+
+// Geodes get cracked rather than collected in the puzzle's telling
+fn robot_name(resource: &str) -> String {
+    match resource {
+        "geode" => "geode-cracking robot".to_string(),
+        _ => format!("{}-collecting robot", resource),
+    }
+}
+
+fn article(name: &str) -> &'static str {
+    if name.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" }
+}
+
+// Like "3 ore and 14 clay"
+fn describe_amounts(blueprint: &Blueprint, amounts: &[u64]) -> String {
+    let items: Vec<String> = amounts.iter().enumerate().filter(|(_idx, amount)| **amount > 0).map(|(idx, amount)| format!("{} {}", amount, blueprint.resources[idx])).collect();
+    match items.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => "nothing".to_string(),
+    }
+}
 
-            if resources.num_ore >= blueprint.clay.ore && resources.num_clay_robots < blueprint.obsidian.clay {
-                new_choices.insert(Resources {
-                    num_ore_robots: resources.num_ore_robots,
-                    num_clay_robots: resources.num_clay_robots + 1,
-                    num_obsidian_robots: resources.num_obsidian_robots,
-                    num_geode_robots: resources.num_geode_robots,
-                    num_ore: resources.num_ore + resources.num_ore_robots - blueprint.clay.ore,
-                    num_clay: resources.num_clay + resources.num_clay_robots,
-                    num_obsidian: resources.num_obsidian + resources.num_obsidian_robots,
-                    num_geode: resources.num_geode + resources.num_geode_robots,
-                });
+// Plays the plan out minute by minute in the words of the puzzle's example
+pub fn narrate(blueprint: &Blueprint, config: &Config, minutes: u64, plan: &Plan) -> String {
+    let start = match blueprint.resource(&config.start) {
+        Some(start) => start,
+        None => return format!("Nothing collects {}, so there's no way to start.\n", config.start),
+    };
+    let mut resources = starting_resources(blueprint, start);
+
+    let mut out = String::new();
+    for minute in 1..=minutes {
+        out.push_str(&format!("== Minute {} ==\n", minute));
+        let building = plan.builds.iter().find(|(build_minute, _robot)| *build_minute == minute).map(|(_minute, robot)| &blueprint.robots[*robot]);
+        if let Some(robot) = building {
+            let name = robot_name(&blueprint.resources[robot.collects]);
+            out.push_str(&format!("Spend {} to start building {} {}.\n", describe_amounts(blueprint, &robot.cost), article(&name), name));
+            for (amount, cost) in resources.amounts.iter_mut().zip(robot.cost.iter()) {
+                *amount -= cost;
             }
+        }
 
-            if resources.num_ore >= blueprint.ore.ore && resources.num_ore_robots < max(max(blueprint.obsidian.ore, blueprint.geode.ore), blueprint.clay.ore) {
-                new_choices.insert(Resources {
-                    num_ore_robots: resources.num_ore_robots + 1,
-                    num_clay_robots: resources.num_clay_robots,
-                    num_obsidian_robots: resources.num_obsidian_robots,
-                    num_geode_robots: resources.num_geode_robots,
-                    num_ore: resources.num_ore + resources.num_ore_robots - blueprint.ore.ore,
-                    num_clay: resources.num_clay + resources.num_clay_robots,
-                    num_obsidian: resources.num_obsidian + resources.num_obsidian_robots,
-                    num_geode: resources.num_geode + resources.num_geode_robots,
-                });
+        for (idx, resource) in blueprint.resources.iter().enumerate() {
+            let robots = resources.robots[idx];
+            if robots == 0 {
+                continue;
             }
+            resources.amounts[idx] += robots;
+            let plural = if robots == 1 { "" } else { "s" };
+            let line = match resource.as_str() {
+                "geode" => {
+                    let total = resources.amounts[idx];
+                    format!("{} geode-cracking robot{} crack{} {} geode{}; you now have {} open geode{}.",
+                            robots, plural, if robots == 1 { "s" } else { "" }, robots, plural, total, if total == 1 { "" } else { "s" })
+                },
+                _ => format!("{} {}{} collect{} {} {}; you now have {} {}.",
+                             robots, robot_name(resource), plural, if robots == 1 { "s" } else { "" }, robots, resource, resources.amounts[idx], resource),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
 
-            new_choices.insert(Resources {
-                num_ore_robots: resources.num_ore_robots,
-                num_clay_robots: resources.num_clay_robots,
-                num_obsidian_robots: resources.num_obsidian_robots,
-                num_geode_robots: resources.num_geode_robots,
-                num_ore: resources.num_ore + resources.num_ore_robots,
-                num_clay: resources.num_clay + resources.num_clay_robots,
-                num_obsidian: resources.num_obsidian + resources.num_obsidian_robots,
-                num_geode: resources.num_geode + resources.num_geode_robots,
-            });
+        if let Some(robot) = building {
+            resources.robots[robot.collects] += 1;
+            out.push_str(&format!("The new {} is ready; you now have {} of them.\n", robot_name(&blueprint.resources[robot.collects]), resources.robots[robot.collects]));
         }
+        out.push('\n');
+    }
+    out
+}
 
-        /*
-        if choices.is_empty() {
-            choices = new_choices;
-        } else {
-            let current_max_geode_resource = *choices.iter().max_by_key(|c| (c.num_geode, c.num_geode_robots)).unwrap();
-            let current_max_geode = current_max_geode_resource.num_geode + ((total_minutes - minute) * current_max_geode_resource.num_geode_robots);
-
-            choices = new_choices.into_iter().filter(|resources| {
-                let mut _resources = *resources;
-                let mut possible_total = resources.num_geode;
-                for future_minute in (minute + 1)..total_minutes {
-                    if _resources.num_ore >= blueprint.geode.ore && _resources.num_obsidian >= blueprint.geode.obsidian {
-                        possible_total += (resources.num_geode_robots + (future_minute - minute)) * (total_minutes - future_minute);
-                    } else {
-                        _resources.num_ore += blueprint.geode.ore;
-                        _resources.num_obsidian += blueprint.geode.obsidian;
-                    }
-                }
-                possible_total >= current_max_geode
-            }).collect();
-        }*/
-        choices = new_choices;
-        minute += 1;
-    }
-}
-
-fn calc_blueprint(blueprint: Blueprint, total_minutes: u64) -> (Blueprint, u64) {
-    let resources = Resources {
-        num_ore_robots: 1,
-        num_clay_robots: 0,
-        num_obsidian_robots: 0,
-        num_geode_robots: 0,
-        num_ore: 0,
-        num_clay: 0,
-        num_obsidian: 0,
-        num_geode: 0,
-    };
+// How each blueprint gets its answer, for the explain param
+fn explain(blueprints: &[Blueprint], plans: &[Plan], config: &Config, minutes: u64) -> String {
+    blueprints.iter().zip(plans).map(|(blueprint, plan)| {
+        format!("Blueprint {}:\n\n{}Most {} blueprint {} can collect in {} minutes: {}\n\n",
+                blueprint.number, narrate(blueprint, config, minutes, plan), config.goal, blueprint.number, minutes, plan.collected)
+    }).collect()
+}
 
-    _calc_blueprint(blueprint, &resources, total_minutes)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    // the resource to collect the most of
+    pub goal: String,
+    // what the one robot there is to begin with collects
+    pub start: String,
+    pub part1_minutes: u64,
+    pub part2_minutes: u64,
+    // print each blueprint's best plan minute by minute before the answer
+    pub explain: bool,
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let blueprints: Vec<_> = read_blueprints(file_path);
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            goal: "geode".to_string(),
+            start: "ore".to_string(),
+            part1_minutes: 24,
+            part2_minutes: 32,
+            explain: false,
+        }
+    }
+}
 
-    let threads: Vec<JoinHandle<(Blueprint, u64)>> = blueprints.into_iter().map(|blueprint| {
-        thread::spawn(move || {
-            calc_blueprint(blueprint, 24)
-        })
-    }).collect();
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["goal", "start", "part1_minutes", "part2_minutes", "explain"];
 
-    let results: Vec<(Blueprint, u64)> = threads.into_iter().map(|handle| {
-        handle.join().unwrap()
-    }).collect();
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "goal" => self.goal = value.to_string(),
+            "start" => self.start = value.to_string(),
+            "part1_minutes" => self.part1_minutes = parse_value(key, value, "a number of minutes")?,
+            "part2_minutes" => self.part2_minutes = parse_value(key, value, "a number of minutes")?,
+            "explain" => self.explain = parse_value(key, value, "true or false")?,
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
+        }
+        Ok(())
+    }
+}
 
-    //let results: Vec<_> = blueprints.iter().map(|blueprint| calc_blueprint(blueprint)).collect();
+pub struct Day19;
 
-    println!("results {:?}", results);
-    results.iter().map(|(blueprint, result)| {
-        blueprint.number as i64 * *result as i64
-    }).sum()
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+    type Input = Vec<Blueprint>;
+    type Config = Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_blueprints(contents)
+    }
+
+    fn part1(blueprints: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        let plans = best_plans(blueprints, config, config.part1_minutes);
+        Ok(explained(quality_levels(blueprints, &plans).into(), config, || explain(blueprints, &plans, config, config.part1_minutes)))
+    }
+
+    fn part2(blueprints: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        let blueprints = part2_blueprints(blueprints);
+        let plans = best_plans(blueprints, config, config.part2_minutes);
+        Ok(explained(most_collected(&plans).into(), config, || explain(blueprints, &plans, config, config.part2_minutes)))
+    }
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let blueprints: Vec<_> = read_blueprints(file_path).iter().take(3).map(|x| *x).collect();
+fn explained(answer: Answer, config: &Config, explanation: impl FnOnce() -> String) -> Answer {
+    if config.explain {
+        answer.explained(explanation())
+    } else {
+        answer
+    }
+}
 
-    let threads: Vec<JoinHandle<(Blueprint, u64)>> = blueprints.into_iter().map(|blueprint| {
-        thread::spawn(move || {
-            calc_blueprint(blueprint, 32)
-        })
-    }).collect();
+fn best_plans(blueprints: &[Blueprint], config: &Config, minutes: u64) -> Vec<Plan> {
+    // the blueprints don't depend on each other, so they're split between the cores
+    let plans = parallel_map(blueprints, default_jobs(), |blueprint| best_plan(blueprint, config, minutes));
+    debug!("results {:?}", plans.iter().map(|plan| plan.collected).collect::<Vec<_>>());
+    plans
+}
 
-    let results: Vec<(Blueprint, u64)> = threads.into_iter().map(|handle| {
-        handle.join().unwrap()
-    }).collect();
+fn quality_levels(blueprints: &[Blueprint], plans: &[Plan]) -> i64 {
+    blueprints.iter().zip(plans).map(|(blueprint, plan)| {
+        blueprint.number as i64 * plan.collected as i64
+    }).sum()
+}
 
-    //let results: Vec<_> = blueprints.iter().map(|blueprint| calc_blueprint(blueprint)).collect();
+// only the first three blueprints survive the elephants in part 2
+fn part2_blueprints(blueprints: &[Blueprint]) -> &[Blueprint] {
+    &blueprints[..blueprints.len().min(3)]
+}
 
-    println!("results {:?}", results);
-    results.iter().map(|(blueprint, result)| {
-        *result as i64
+fn most_collected(plans: &[Plan]) -> i64 {
+    plans.iter().map(|plan| {
+        plan.collected as i64
     }).product()
-}
\ No newline at end of file
+}
+
+pub fn part1(blueprints: &[Blueprint], config: &Config) -> i64 {
+    quality_levels(blueprints, &best_plans(blueprints, config, config.part1_minutes))
+}
+
+pub fn part2(blueprints: &[Blueprint], config: &Config) -> i64 {
+    most_collected(&best_plans(part2_blueprints(blueprints), config, config.part2_minutes))
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.\n",
+        "Blueprint 2:\n",
+        "  Each ore robot costs 2 ore.\n",
+        "  Each clay robot costs 3 ore.\n",
+        "  Each obsidian robot costs 3 ore and 8 clay.\n",
+        "  Each geode robot costs 3 ore and 12 obsidian.\n",
+    );
+    let blueprints = Day19::parse(contents).unwrap();
+    assert_eq!(blueprints[1].resources, vec!["ore", "clay", "obsidian", "geode"]);
+    assert_eq!(blueprints[1].robots[2], Robot { collects: 2, cost: vec![3, 8, 0, 0] });
+    let config = Config::default();
+    assert_eq!(part1(&blueprints, &config), 33);
+    assert_eq!(part2(&blueprints, &config), 56 * 62);
+
+    // replaying the plan ends up with as many geodes as the search said
+    let plan = best_plan(&blueprints[0], &config, 24);
+    let story = narrate(&blueprints[0], &config, 24, &plan);
+    assert!(story.starts_with("== Minute 1 ==\n1 ore-collecting robot collects 1 ore; you now have 1 ore.\n\n"));
+    assert!(story.contains("Spend 2 ore to start building a clay-collecting robot.\n"));
+    assert!(story.trim_end().ends_with("you now have 9 open geodes."));
+    assert_eq!(plan.builds.iter().filter(|(_minute, robot)| *robot == 3).count(), 2);
+
+    // a made up factory where diamonds need both gems and the resources are spelled out in any order
+    let factory = Day19::parse("Blueprint 1: Each coal robot costs 1 coal. Each ruby robot costs 2 coal. Each diamond robot costs 1 ruby, 1 coal and 2 ruby.\n").unwrap();
+    assert_eq!(factory[0].robots[2].cost, vec![1, 3, 0]);
+    let config = Config { goal: "diamond".to_string(), start: "coal".to_string(), ..Config::default() };
+    assert!(calc_blueprint(&factory[0], &config, 10) > 0);
+    assert_eq!(calc_blueprint(&factory[0], &Config::default(), 10), 0);
+
+    let err = Day19::parse("Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs lots.\n").unwrap_err();
+    assert_eq!((err.line, err.column, err.text.as_str()), (1, 64, "lots"));
+    assert_eq!(Day19::parse("Each ore robot costs 4 ore.\n").unwrap_err().line, 1);
+    assert_eq!(Day19::parse("Blueprint 1:\nBlueprint 2: Each ore robot costs 4 ore.\n").unwrap_err().line, 1);
+}
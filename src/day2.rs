@@ -1,6 +1,40 @@
-use std::fs;
+use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-fn calc_score(tups: Vec<Vec<&str>>) -> u64 {
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    type Input = Vec<Vec<String>>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse_rounds(contents)
+    }
+
+    fn part1(rounds: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(rounds).into())
+    }
+
+    fn part2(rounds: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(rounds).into())
+    }
+}
+
+pub fn parse_rounds(contents: &str) -> Result<Vec<Vec<String>>, ParseError> {
+    get_trimmed_lines(contents)
+        .iter()
+        .map(|line| {
+            match line.split(' ').collect::<Vec<&str>>()[..] {
+                [opponent @ ("A" | "B" | "C"), me @ ("X" | "Y" | "Z")] => Ok(vec![opponent.to_string(), me.to_string()]),
+                _ => Err(line.error("a round like \"A Y\""))
+            }
+        })
+        .collect()
+}
+
+pub fn calc_score(tups: Vec<Vec<&str>>) -> u64 {
     let mut score = 0;
     for tup in tups {
         let opponent_choice = tup[0];
@@ -37,28 +71,14 @@ fn calc_score(tups: Vec<Vec<&str>>) -> u64 {
     score
 }
 
-pub fn part1(file_path: &str) -> u64 {
-    let contents = fs::read_to_string(file_path).unwrap();
-
-    let tups: Vec<Vec<&str>> = contents
-        .split("\n")
-        .map(|line| line.trim())
-        .filter(|line| line.len() > 0)
-        .map(|line| line.split(" ").collect())
-        .collect();
+pub fn part1(rounds: &[Vec<String>]) -> u64 {
+    let tups: Vec<Vec<&str>> = rounds.iter().map(|round| round.iter().map(|s| s.as_str()).collect()).collect();
 
     calc_score(tups)
 }
 
-pub fn part2(file_path: &str) -> u64 {
-    let contents = fs::read_to_string(file_path).unwrap();
-
-    let tups: Vec<Vec<&str>> = contents
-        .split("\n")
-        .map(|line| line.trim())
-        .filter(|line| line.len() > 0)
-        .map(|line| line.split(" ").collect())
-        .collect();
+pub fn part2(rounds: &[Vec<String>]) -> u64 {
+    let tups: Vec<Vec<&str>> = rounds.iter().map(|round| round.iter().map(|s| s.as_str()).collect()).collect();
 
     let adjusted_pairs: Vec<Vec<&str>> = tups.iter().map(
         |pair| {
@@ -92,3 +112,15 @@ pub fn part2(file_path: &str) -> u64 {
 
     calc_score(adjusted_pairs)
 }
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "A Y\n",
+        "B X\n",
+        "C Z\n",
+    );
+    let rounds = Day2::parse(contents).unwrap();
+    assert_eq!(part1(&rounds), 15);
+    assert_eq!(part2(&rounds), 12);
+}
@@ -1,46 +1,23 @@
-use std::cmp::{max, min};
+use log::debug;
 use crate::common::get_trimmed_lines;
-
-#[derive(Debug)]
-struct Indexes {
-    indexes: Vec<i64>,
-}
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 fn adjusted_index(numbers_len: usize, index: i64) -> usize {
     (if index >= 0 {
-        (index % numbers_len as i64)
+        index % numbers_len as i64 
     } else {
-        (numbers_len as i64 - ((index * -1) as usize % numbers_len) as i64)
+        numbers_len as i64 - (-index as usize % numbers_len) as i64 
     }) as usize
 }
 
-fn shift(indexes: &mut Indexes, old_index: usize, shift: i64) {
-    if shift == 0 {
-        return;
-    }
-
-    let indexes_len = indexes.indexes.len() - 1;
-    let dest_index = adjusted_index(indexes_len, old_index as i64 + shift);
-    let direction: i64 = if dest_index > old_index { -1 } else { 1 };
-    let diff = dest_index as i64 - old_index as i64;
-
-    indexes.indexes[dest_index] -= diff;
-    let mut index = (dest_index as i64 + direction) as usize;
-    while index != old_index {
-        indexes.indexes[index] -= direction;
-
-        index = (index as i64 + direction) as usize;
-    }
-    indexes.indexes[index] -= direction;
-}
-
-fn nth(numbers: &Vec<i64>, index: i64) -> i64 {
+fn nth(numbers: &[i64], index: i64) -> i64 {
     numbers[adjusted_index(numbers.len(), index)]
 }
 
-fn mix (numbers: &Vec<i64>, count: usize) -> Vec<i64> {
-    let mut _indexes: Vec<usize> = (0..numbers.len()).map(|x| x).collect();
-    let mut _numbers=  numbers.clone();
+pub fn mix(numbers: &[i64], count: usize) -> Vec<i64> {
+    let mut _indexes: Vec<usize> = (0..numbers.len()).collect();
+    let mut _numbers=  numbers.to_vec();
     //println!("initial {:?}", numbers);
 
     for _ in 0..count {
@@ -56,11 +33,11 @@ fn mix (numbers: &Vec<i64>, count: usize) -> Vec<i64> {
 
             let mut new_index = adjusted_old_index as i64 + *shift;
             if new_index < 0 {
-                new_index = ((numbers.len() as i64 - 1) - (-new_index % (numbers.len() as i64 - 1)));
+                new_index = (numbers.len() as i64 - 1) - (-new_index % (numbers.len() as i64 - 1)) ;
                 //new_index = new_index + numbers.len() as i64 - 1;
             }
             // 0, 1, 2, 3, 4, 5, 6
-            new_index = new_index % (numbers.len() as i64 - 1);
+            new_index %= numbers.len() as i64 - 1 ;
 
             if new_index > adjusted_old_index as i64 {
                 //new_index -= 1;
@@ -78,10 +55,38 @@ fn mix (numbers: &Vec<i64>, count: usize) -> Vec<i64> {
     _numbers
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let numbers: Vec<i64> = get_trimmed_lines(file_path).iter().map(|line| line.parse().unwrap()).collect();
+pub struct Day20;
 
-    let mixed = mix(&numbers, 1);
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+    type Input = Vec<i64>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+        let numbers: Vec<i64> = lines.iter().map(|line| line.parse_at(line, "an integer")).collect::<Result<_, _>>()?;
+        if numbers.iter().filter(|number| **number == 0).count() != 1 {
+            return Err(ParseError::new(1, 1, "", "exactly one 0 in the file"));
+        }
+        // mixing moves numbers around the others, so there have to be some
+        if numbers.len() < 2 {
+            let end = lines.last().map(|line| line.number + 1).unwrap_or(1);
+            return Err(ParseError::new(end, 1, "", "a second number to mix"));
+        }
+        Ok(numbers)
+    }
+
+    fn part1(numbers: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(numbers).into())
+    }
+
+    fn part2(numbers: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(numbers).into())
+    }
+}
+
+pub fn part1(numbers: &[i64]) -> i64 {
+    let mixed = mix(numbers, 1);
     let zero = mixed.iter().enumerate().find_map(|(i, x)| {
         if *x == 0 {
             Some(i)
@@ -95,9 +100,9 @@ pub fn part1(file_path: &str) -> i64 {
     nth(&mixed, 1000 + zero) + nth(&mixed, 2000 + zero) + nth(&mixed, 3000 + zero)
 }
 
-pub fn part2(file_path: &str) -> i64 {
+pub fn part2(numbers: &[i64]) -> i64 {
     const DECRYPTION: i64 = 811589153;
-    let numbers: Vec<i64> = get_trimmed_lines(file_path).iter().map(|line| line.parse().unwrap()).map(|number: i64| number * DECRYPTION).collect();
+    let numbers: Vec<i64> = numbers.iter().map(|number| number * DECRYPTION).collect();
     let mixed = mix(&numbers, 10);
 
     let zero = mixed.iter().enumerate().find_map(|(i, x)| {
@@ -108,6 +113,22 @@ pub fn part2(file_path: &str) -> i64 {
         }
     }).unwrap() as i64;
 
-    println!("{} {} {} {}", zero, nth(&mixed, 1000 + zero), nth(&mixed, 2000 + zero), nth(&mixed, 3000 + zero));
+    debug!("{} {} {} {}", zero, nth(&mixed, 1000 + zero), nth(&mixed, 2000 + zero), nth(&mixed, 3000 + zero));
     nth(&mixed, 1000 + zero) + nth(&mixed, 2000 + zero) + nth(&mixed, 3000 + zero)
 }
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "1\n",
+        "2\n",
+        "-3\n",
+        "3\n",
+        "-2\n",
+        "0\n",
+        "4\n",
+    );
+    let numbers = Day20::parse(contents).unwrap();
+    assert_eq!(part1(&numbers), 3);
+    assert_eq!(part2(&numbers), 1623178306);
+}
@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use lazy_static::lazy_static;
 use regex::Regex;
-use crate::common::get_trimmed_lines;
+use crate::common::{get_trimmed_lines, Line};
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 #[derive(Debug, Copy, Clone)]
-enum Operand {
+pub enum Operand {
     Add,
     Sub,
     Mul,
@@ -13,36 +15,40 @@ enum Operand {
 }
 
 #[derive(Debug)]
-struct Operation {
-    operand: Operand,
-    a: String,
-    b: String,
+pub struct Operation {
+    pub operand: Operand,
+    pub a: String,
+    pub b: String,
 }
 
 #[derive(Debug)]
-enum Value {
+pub enum Value {
     Operation(Operation),
     Const(i64)
 }
 
 #[derive(Debug)]
-struct Monkey {
-    value: Value,
-    name: String,
+pub struct Monkey {
+    pub value: Value,
+    pub name: String,
+    // where the monkey is in the input, for errors found while solving
+    pub line: Line,
 }
 
 lazy_static! {
-    static ref RE_CONST: Regex = Regex::new(r"([a-z]+): (\d+)").unwrap();
-    static ref RE_OP: Regex = Regex::new(r"([a-z]+): ([a-z]+) ([\+\-\*/]) ([a-z]+)").unwrap();
+    static ref RE_CONST: Regex = Regex::new(r"^([a-z]+): (-?\d+)$").unwrap();
+    static ref RE_OP: Regex = Regex::new(r"^([a-z]+): ([a-z]+) ([\+\-\*/]) ([a-z]+)$").unwrap();
 }
 
-fn parse(file_path: &str) -> HashMap<String, Rc<Monkey>> {
-    let monkeys: Vec<Rc<Monkey>> = get_trimmed_lines(file_path).iter().map(|line| {
-        Rc::new(match RE_CONST.captures(line) {
+pub fn parse(contents: &str) -> Result<HashMap<String, Rc<Monkey>>, ParseError> {
+    let lines = get_trimmed_lines(contents);
+    let monkeys: Vec<Rc<Monkey>> = lines.iter().map(|line| {
+        Ok(Rc::new(match RE_CONST.captures(line) {
             Some(cap) => {
                 Monkey {
                     name: cap.get(1).unwrap().as_str().to_string(),
-                    value: Value::Const(cap.get(2).unwrap().as_str().parse::<i64>().unwrap())
+                    value: Value::Const(line.parse_at(cap.get(2).unwrap().as_str(), "a number")?),
+                    line: line.clone(),
                 }
             },
             None => {
@@ -58,36 +64,120 @@ fn parse(file_path: &str) -> HashMap<String, Rc<Monkey>> {
                                     "+" => Operand::Add,
                                     "-" => Operand::Sub,
                                     "*" => Operand::Mul,
-                                    "/" => Operand::Div,
-                                    _ => { panic!("unknown operand"); }
+                                    _ => Operand::Div,
                                 },
                                 a,
                                 b
                             }),
+                            line: line.clone(),
                         }
                     },
                     None => {
-                        panic!("no match for line {}", line);
+                        return Err(line.error("a monkey like \"root: pppw + sjmn\" or \"dbpl: 5\""));
                     }
                 }
             }
-        })
-    }).collect();
-    monkeys.into_iter().map(|monkey| (monkey.name.to_string(), monkey)).collect()
+        }))
+    }).collect::<Result<_, _>>()?;
+    let mut names = HashSet::new();
+    for monkey in monkeys.iter() {
+        if !names.insert(monkey.name.as_str()) {
+            return Err(monkey.line.error_at(&monkey.line[..monkey.name.len()], "a monkey which isn't listed already"));
+        }
+    }
+    let monkeys: HashMap<String, Rc<Monkey>> = monkeys.into_iter().map(|monkey| (monkey.name.to_string(), monkey)).collect();
+
+    for line in lines.iter() {
+        if let Some(cap) = RE_OP.captures(line) {
+            for operand in [cap.get(2).unwrap(), cap.get(4).unwrap()] {
+                if !monkeys.contains_key(operand.as_str()) {
+                    return Err(line.error_at(&line[operand.range()], "the name of a monkey in the input"));
+                }
+            }
+        }
+    }
+    let mut waiting = HashMap::new();
+    for line in lines.iter() {
+        if let Some(cap) = RE_OP.captures(line) {
+            let name = monkeys.get_key_value(cap.get(1).unwrap().as_str()).unwrap().0;
+            if let Some((monkey, operand)) = find_cycle(&monkeys, name, &mut waiting) {
+                // point at the operand of the monkey where the cycle closes, which is on its own line
+                let (line, cap) = lines.iter().find_map(|line| RE_OP.captures(line).filter(|cap| &cap[1] == monkey).map(|cap| (line, cap))).unwrap();
+                let operand = [cap.get(2).unwrap(), cap.get(4).unwrap()].into_iter().find(|found| found.as_str() == operand).unwrap();
+                return Err(line.error_at(&line[operand.range()], &format!("a monkey which isn't waiting for {}", monkey)));
+            }
+        }
+    }
+    // working out every number now means solving can't divide by zero or overflow later
+    let mut yells = HashMap::new();
+    for line in lines.iter() {
+        let name = monkeys.get_key_value(&line[..line.find(':').unwrap()]).unwrap().0;
+        checked_yell(&monkeys, name, &mut yells)?;
+    }
+    if !monkeys.contains_key("root") {
+        let end = lines.last().map(|line| line.number + 1).unwrap_or(1);
+        return Err(ParseError::new(end, 1, "", "a monkey named root"));
+    }
+
+    Ok(monkeys)
+}
+
+// A monkey waiting for a monkey which is already waiting for it, which would never yell. waiting is false for
+// the monkeys being followed and true for those known to yell.
+fn find_cycle<'a>(monkeys: &'a HashMap<String, Rc<Monkey>>, name: &'a str, waiting: &mut HashMap<&'a str, bool>) -> Option<(&'a str, &'a str)> {
+    if waiting.contains_key(name) {
+        return None;
+    }
+    waiting.insert(name, false);
+    if let Value::Operation(op) = &monkeys[name].value {
+        for operand in [op.a.as_str(), op.b.as_str()] {
+            if waiting.get(operand) == Some(&false) {
+                return Some((name, operand));
+            }
+            if let Some(cycle) = find_cycle(monkeys, operand, waiting) {
+                return Some(cycle);
+            }
+        }
+    }
+    waiting.insert(name, true);
+    None
+}
+
+fn checked_yell<'a>(monkeys: &'a HashMap<String, Rc<Monkey>>, name: &'a str, yells: &mut HashMap<&'a str, i64>) -> Result<i64, ParseError> {
+    if let Some(value) = yells.get(name) {
+        return Ok(*value);
+    }
+
+    let monkey = &monkeys[name];
+    let value = match &monkey.value {
+        Value::Const(num) => *num,
+        Value::Operation(op) => {
+            let a = checked_yell(monkeys, &op.a, yells)?;
+            let b = checked_yell(monkeys, &op.b, yells)?;
+            let line = &monkey.line;
+            match op.operand {
+                Operand::Div if b == 0 => {
+                    // the monkey divided by is the last word on the line
+                    return Err(line.error_at(&line[line.rfind(' ').unwrap() + 1..], "a monkey which doesn't yell 0 to divide by"));
+                },
+                Operand::Add => a.checked_add(b),
+                Operand::Sub => a.checked_sub(b),
+                Operand::Mul => a.checked_mul(b),
+                Operand::Div => a.checked_div(b),
+            }.ok_or_else(|| line.error("a monkey which yells a number that fits in 64 bits"))?
+        }
+    };
+    yells.insert(name, value);
+    Ok(value)
 }
 
-fn resolve(monkeys: &HashMap<String, Rc<Monkey>>, name: &str, error_on_name: Option<&str>) -> i64 {
+pub fn resolve(monkeys: &HashMap<String, Rc<Monkey>>, name: &str, error_on_name: Option<&str>) -> i64 {
     _resolve(monkeys, name, &mut HashMap::new(), error_on_name)
 }
 
 fn _resolve(monkeys: &HashMap<String, Rc<Monkey>>, name: &str, lookup: &mut HashMap<String, i64>, error_on_name: Option<&str>) -> i64 {
-    match error_on_name {
-        None => {},
-        Some(_error_on_name) => {
-            if name == _error_on_name {
-                panic!("error")
-            }
-        }
+    if error_on_name == Some(name) {
+        panic!("error")
     }
 
     if lookup.contains_key(name) {
@@ -102,10 +192,10 @@ fn _resolve(monkeys: &HashMap<String, Rc<Monkey>>, name: &str, lookup: &mut Hash
             let a = &_op.a;
             let b = &_op.b;
             match _op.operand {
-                Operand::Add => _resolve(&monkeys, &a, lookup, error_on_name) + _resolve(&monkeys, &b, lookup, error_on_name),
-                Operand::Sub => _resolve(&monkeys, &a, lookup, error_on_name) - _resolve(&monkeys, &b, lookup, error_on_name),
-                Operand::Mul => _resolve(&monkeys, &a, lookup, error_on_name) * _resolve(&monkeys, &b, lookup, error_on_name),
-                Operand::Div => _resolve(&monkeys, &a, lookup, error_on_name) / _resolve(&monkeys, &b, lookup, error_on_name),
+                Operand::Add => _resolve(monkeys, a, lookup, error_on_name) + _resolve(monkeys, b, lookup, error_on_name),
+                Operand::Sub => _resolve(monkeys, a, lookup, error_on_name) - _resolve(monkeys, b, lookup, error_on_name),
+                Operand::Mul => _resolve(monkeys, a, lookup, error_on_name) * _resolve(monkeys, b, lookup, error_on_name),
+                Operand::Div => _resolve(monkeys, a, lookup, error_on_name) / _resolve(monkeys, b, lookup, error_on_name),
             }
         }
     };
@@ -113,11 +203,30 @@ fn _resolve(monkeys: &HashMap<String, Rc<Monkey>>, name: &str, lookup: &mut Hash
     result
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let monkeys = parse(file_path);
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+    type Input = HashMap<String, Rc<Monkey>>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse(contents)
+    }
+
+    fn part1(monkeys: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(monkeys).into())
+    }
+
+    fn part2(monkeys: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(monkeys)?.into())
+    }
+}
+
+pub fn part1(monkeys: &HashMap<String, Rc<Monkey>>) -> i64 {
     //println!("{:?}", monkeys);
 
-    resolve(&monkeys, "root", None)
+    resolve(monkeys, "root", None)
 }
 
 
@@ -125,18 +234,18 @@ pub fn part1(file_path: &str) -> i64 {
 // result = 0, return calc_solve(3, (4+x))
 // result = 3, return calc_solve(3 - 4, x)
 // result = -1, return -1
-fn calc_solve(operand: Option<Operand>, stack: &Vec<String>, monkeys: &HashMap<String, Rc<Monkey>>, result: i64) -> i64 {
-    let mut stack_popped = stack.clone();
+fn calc_solve(operand: Option<Operand>, stack: &[String], monkeys: &HashMap<String, Rc<Monkey>>, result: i64) -> Result<i64, ParseError> {
+    let mut stack_popped = stack.to_vec();
     match stack_popped.pop() {
         None => {
-            panic!("unexpected")
+            unreachable!("the stack ends at humn, which yells a number")
         },
         Some(_parent) => {
             let parent_monkey = monkeys[&_parent].clone();
             match &parent_monkey.value {
                 Value::Const(_) => {
                     //humn
-                    result
+                    Ok(result)
                 },
                 Value::Operation(_parent_op) => {
                     // a op b = result
@@ -144,31 +253,33 @@ fn calc_solve(operand: Option<Operand>, stack: &Vec<String>, monkeys: &HashMap<S
 
                     match stack_popped.last() {
                         None => {
-                            panic!("unexpected");
+                            unreachable!("the stack ends at humn, which yells a number");
                         },
                         Some(_last) => {
                             let last_monkey = monkeys[_last.as_str()].clone();
                             let new_result = if _last.as_str() == _parent_op.a.as_str() {
                                 // result = humn op const-side
-                                let const_side = resolve(&monkeys, _parent_op.b.as_str(), Some("humn"));
+                                let const_side = resolve(monkeys, _parent_op.b.as_str(), Some("humn"));
                                 //let humn_side = calc_solve(_last_op.operand, , stack, monkeys);
 
                                 match _operand {
-                                    Operand::Add => result - const_side,
-                                    Operand::Sub => result + const_side,
-                                    Operand::Mul => result / const_side,
-                                    Operand::Div => result * const_side
+                                    Operand::Add => result.checked_sub(const_side),
+                                    Operand::Sub => result.checked_add(const_side),
+                                    Operand::Mul => result.checked_div(const_side),
+                                    Operand::Div => result.checked_mul(const_side)
                                 }
                             } else {
                                 // result = const-side op humn-side
-                                let const_side = resolve(&monkeys, _parent_op.a.as_str(), Some("humn"));
+                                let const_side = resolve(monkeys, _parent_op.a.as_str(), Some("humn"));
                                 match _operand {
-                                    Operand::Add => result - const_side,
-                                    Operand::Sub => const_side - result,
-                                    Operand::Mul => result / const_side,
-                                    Operand::Div => const_side / result
+                                    Operand::Add => result.checked_sub(const_side),
+                                    Operand::Sub => const_side.checked_sub(result),
+                                    Operand::Mul => result.checked_div(const_side),
+                                    Operand::Div => const_side.checked_div(result)
                                 }
                             };
+                            // multiplying by 0 for example can't be undone
+                            let new_result = new_result.ok_or_else(|| parent_monkey.line.error("an operation which can be undone to work out humn's number"))?;
                             match &last_monkey.value {
                                 Value::Const(_) => {
                                     calc_solve(None, &stack_popped, monkeys, new_result)
@@ -186,28 +297,46 @@ fn calc_solve(operand: Option<Operand>, stack: &Vec<String>, monkeys: &HashMap<S
 
 }
 
-fn solve(lookup: &HashMap<String, Vec<Rc<Monkey>>>, monkeys: &HashMap<String, Rc<Monkey>>, stack: &Vec<String>) -> i64 {
+fn solve(lookup: &HashMap<String, Vec<Rc<Monkey>>>, monkeys: &HashMap<String, Rc<Monkey>>, stack: &[String]) -> Result<i64, ParseError> {
     let current = stack.last().unwrap();
-    if lookup[current].len() > 1 {
-        panic!("help");
-    }
-
-    let parent = lookup[current][0].clone();
-    let mut stack_copy = stack.clone();
+    let parent = match lookup.get(current).map(|waiting| &waiting[..]) {
+        Some([parent]) => parent.clone(),
+        Some(_) => {
+            let line = &monkeys[current].line;
+            return Err(line.error_at(&line[..current.len()], "a monkey which only one monkey waits for, since humn's number goes through it"));
+        },
+        // nothing waits for it, so it isn't root either
+        None => return Err(monkeys["humn"].line.error("a humn which root waits for")),
+    };
+    let mut stack_copy = stack.to_vec();
     stack_copy.push(parent.name.to_string());
     if parent.name == "root" {
         // result = humn-side - const-side
         calc_solve(Some(Operand::Sub),  &stack_copy, monkeys, 0)
     } else {
-        solve(lookup, &monkeys, &stack_copy)
+        solve(lookup, monkeys, &stack_copy)
     }
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let monkeys = parse(file_path);
+pub fn part2(monkeys: &HashMap<String, Rc<Monkey>>) -> Result<i64, ParseError> {
+    // part 1 doesn't need a humn
+    let humn = match monkeys.get("humn") {
+        Some(humn) => humn,
+        None => {
+            let end = monkeys.values().map(|monkey| monkey.line.number + 1).max().unwrap_or(1);
+            return Err(ParseError::new(end, 1, "", "a monkey named humn"));
+        }
+    };
+    if let Value::Operation(_) = humn.value {
+        return Err(humn.line.error("a humn which yells a number"));
+    }
+    let root = &monkeys["root"];
+    if let Value::Const(_) = root.value {
+        return Err(root.line.error("a root which waits for two monkeys"));
+    }
 
     let mut lookup: HashMap<String, Vec<Rc<Monkey>>> = HashMap::new();
-    for (name, monkey) in monkeys.iter() {
+    for (_name, monkey) in monkeys.iter() {
         match &monkey.value {
             Value::Const(_) => {},
             Value::Operation(_op) => {
@@ -219,6 +348,30 @@ pub fn part2(file_path: &str) -> i64 {
         }
     }
 
-    solve(&lookup, &monkeys,&mut vec!["humn".to_string()])
+    solve(&lookup, monkeys, &["humn".to_string()])
 }
 
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "root: pppw + sjmn\n",
+        "dbpl: 5\n",
+        "cczh: sllz + lgvd\n",
+        "zczc: 2\n",
+        "ptdq: humn - dvpt\n",
+        "dvpt: 3\n",
+        "lfqf: 4\n",
+        "humn: 5\n",
+        "ljgn: 2\n",
+        "sjmn: drzm * dbpl\n",
+        "sllz: 4\n",
+        "pppw: cczh / lfqf\n",
+        "lgvd: ljgn * ptdq\n",
+        "drzm: hmdt - zczc\n",
+        "hmdt: 32\n",
+    );
+    let monkeys = Day21::parse(contents).unwrap();
+    assert_eq!(part1(&monkeys), 152);
+    assert_eq!(part2(&monkeys), Ok(301));
+}
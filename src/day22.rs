@@ -1,21 +1,23 @@
-use std::cmp::max;
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::ops::Rem;
-use array2d::Array2D;
-use nalgebra::UnitQuaternion;
-
-struct Board {
-    board: Array2D<char>
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+use nalgebra::{UnitQuaternion, Vector3};
+use crate::common::{numbered_lines, Grid, Line};
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+
+pub struct Board {
+    board: Grid<char>,
+    // the map's lines, to point at the tiles which stop it folding into a cube
+    lines: Vec<Line>,
 }
 
 #[derive(Debug)]
-enum Turn {
+pub enum Turn {
     Right,
     Left
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Direction {
     Right,
     Up,
@@ -23,84 +25,75 @@ enum Direction {
     Down
 }
 
-enum Move {
+pub enum Move {
     Turn(Turn),
     Forward(usize)
 }
 
-type Path = Vec<Move>;
-
-fn parse(file_path: &str) -> (Board, Path) {
-    let file_contents = fs::read_to_string(file_path).unwrap();
+pub type Path = Vec<Move>;
 
-    //let lines: Vec<String> = file_contents.split("\n").map(|line| line.trim()).map(|line| line.to_string()).collect();
-    let mut board_lines: Vec<String> = Vec::new();
-    let lines: Vec<&str> = file_contents.split("\n").collect();
+pub fn parse(file_contents: &str) -> Result<(Board, Path), ParseError> {
+    let mut board_lines: Vec<Line> = Vec::new();
+    let lines = numbered_lines(file_contents);
     let mut is_board_lines = true;
-    let mut path_line: Option<Vec<char>> = None;
+    let mut path_line: Option<Line> = None;
     for line in lines.iter() {
-        if line.is_empty() {
-            is_board_lines = false;
+        if line.trim().is_empty() {
+            is_board_lines = board_lines.is_empty();
             continue;
         }
         if is_board_lines {
-            board_lines.push(line.to_string());
+            if let Some(idx) = line.find(|c| c != ' ' && c != '.' && c != '#') {
+                return Err(line.error_at(&line[idx..], "a tile ' ', '.' or '#'"));
+            }
+            board_lines.push(line.clone());
+        } else if path_line.is_none() {
+            path_line = Some(line.trimmed());
         } else {
-            path_line = Some(line.chars().collect());
+            return Err(line.error("the end of the input after the path"));
         }
     }
 
-    let num_cols = board_lines.iter().max_by_key(|line| line.len()).unwrap().len();
-    let rows: Vec<Vec<char>> = board_lines.iter().map(|line| {
-        let mut chars: Vec<char> = line.chars().collect();
-
-        for _ in chars.len()..num_cols {
-            chars.push(' ');
-        }
-
-        chars
-    } ).collect();
+    let end = lines.len() + 1;
+    if board_lines.is_empty() {
+        return Err(ParseError::new(end, 1, "", "a map of the board"));
+    }
+    // the right hand side of the map isn't padded with spaces
+    let rows: Vec<Vec<char>> = board_lines.iter().map(|line| line.chars().collect()).collect();
     let board = Board {
-        board: Array2D::from_rows(&rows).unwrap()
+        board: Grid::from_rows(&rows, ' '),
+        lines: board_lines,
     };
+    if calc_start_position(&board).is_none() {
+        return Err(board.lines[0].error("an open tile '.' to start from"));
+    }
 
+    let path_line = path_line.ok_or_else(|| ParseError::new(end, 1, "", "a path after a blank line"))?;
     let mut path: Vec<Move> = Vec::new();
-    let mut work: Vec<char> = Vec::new();
-    for c in path_line.unwrap() {
-        match c {
-            'L' => {
-                let s: String = work.iter().collect();
-                let steps = s.parse::<usize>().unwrap();
-                path.push(Move::Forward(steps));
-                path.push(Move::Turn(Turn::Left));
-                work.clear();
-            },
-            'R' => {
-                let s: String = work.iter().collect();
-                let steps = s.parse::<usize>().unwrap();
-                path.push(Move::Forward(steps));
-                path.push(Move::Turn(Turn::Right));
-                work.clear();
-            },
-            _ => {
-                work.push(c);
-            }
-        }
+    let mut start = 0;
+    for (idx, c) in path_line.char_indices() {
+        let turn = match c {
+            'L' => Turn::Left,
+            'R' => Turn::Right,
+            '0'..='9' => continue,
+            _ => return Err(path_line.error_at(&path_line[idx..], "a number of steps, L or R"))
+        };
+        path.push(Move::Forward(path_line.parse_at(&path_line[start..idx], "a number of steps")?));
+        path.push(Move::Turn(turn));
+        start = idx + 1;
     }
 
-    if !work.is_empty() {
-        let s: String = work.iter().collect();
-        let steps = s.parse::<usize>().unwrap();
-        path.push(Move::Forward(steps));
+    if start < path_line.len() {
+        path.push(Move::Forward(path_line.parse_at(&path_line[start..], "a number of steps")?));
     }
 
-    (board, path)
+    Ok((board, path))
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Coord {
-    row: i64,
-    col: i64
+pub struct Coord {
+    pub row: i64,
+    pub col: i64
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -117,7 +110,7 @@ struct Position {
 
 impl Board {
     pub fn get(self: &Board, coord: Coord) -> Option<&char> {
-        self.board.get(coord.row as usize, coord.col as usize)
+        self.board.get((coord.row, coord.col))
     }
 
     pub fn num_columns(self: &Board) -> usize {
@@ -127,6 +120,18 @@ impl Board {
     pub fn num_rows(self: &Board) -> usize {
         self.board.num_rows()
     }
+
+    // part 2 folds the board up, so it has to be a cube net
+    fn cube(self: &Board) -> Result<Cube, ParseError> {
+        calc_cube(self).map_err(|(coord, expected)| match coord {
+            Some(coord) => {
+                let line = &self.lines[coord.row as usize];
+                let col = (coord.col as usize).min(line.len());
+                line.error_at(&line[col..(col + 1).min(line.len())], &expected)
+            },
+            None => self.lines[0].error(&expected),
+        })
+    }
 }
 
 struct Cube {
@@ -146,6 +151,8 @@ enum SideDirection {
 
 type Quat = UnitQuaternion<f64>;
 
+// coord is the top left corner of the side on the board. The quaternion rotates the side's
+// local frame (x = column, y = row, z = out of the page) into its orientation on the cube
 #[derive(Debug, Copy, Clone, PartialEq)]
 struct Side {
     coord: Coord,
@@ -153,195 +160,199 @@ struct Side {
     quaternion: Quat,
 }
 
-fn _calc_cube(board: &Board, sides: &mut HashMap<SideDirection, Side>, current_side: Side) {
-    sides.insert(current_side.direction, current_side);
-
-    panic!("??");
-
+fn to_increment(direction: Direction) -> Increment {
+    match direction {
+        Direction::Right => Increment { row: 0, col: 1 },
+        Direction::Down => Increment { row: 1, col: 0 },
+        Direction::Left => Increment { row: 0, col: -1 },
+        Direction::Up => Increment { row: -1, col: 0 },
+    }
 }
-/*
-fn calc_cube(board: &Board) -> Cube {
-    let len_of_side = (max(board.num_columns(), board.num_rows()) / 4) as i64;
-
-    let start = calc_start_position(board);
-    let mut sides = HashMap::new();
-    _calc_cube(board, &mut sides, Side {
-        coord: start.coord,
-        direction: SideDirection::Top,
-        quaternion: UnitQuaternion::from_euler_angles(0f64, 0f64, 0f64),
-    });
 
-    Cube { sides, len_of_side }
+fn to_direction(increment: Increment) -> Direction {
+    match increment {
+        Increment { row: 0, col: 1 } => Direction::Right,
+        Increment { row: 1, col: 0 } => Direction::Down,
+        Increment { row: 0, col: -1 } => Direction::Left,
+        Increment { row: -1, col: 0 } => Direction::Up,
+        _ => panic!("unexpected increment {:?}", increment)
+    }
 }
 
-fn lookup_side(cube: &Cube, coord: Coord) -> Option<Side> {
-    cube.sides.values().find_map(|side| {
-        if coord.row >= coord.row && coord.row < coord.row + cube.len_of_side && coord.col >= coord.col && coord.col < coord.col + cube.len_of_side {
-            Some(*side)
-        } else {
-            None
-        }
-    })
+fn round_vector(vector: Vector3<f64>) -> (i64, i64, i64) {
+    (vector.x.round() as i64, vector.y.round() as i64, vector.z.round() as i64)
 }
 
-fn transform(coord: Increment, transformation: &Quat, len_of_side: i64) -> Increment {
-    let mut adjusted_new_coord = rotate(
-        len_of_side,
-        Increment {
-            row: coord.row.rem_euclid(len_of_side),
-            col: coord.col.rem_euclid(len_of_side),
-        },
-        transformation.rotation_count
-    );
-
-    Increment {
-        row: if transformation.flip_row { len_of_side - 1 - adjusted_new_coord.row } else { adjusted_new_coord.row },
-        col: if transformation.flip_col { len_of_side - 1 - adjusted_new_coord.col } else { adjusted_new_coord.col },
+fn to_side_direction(normal: Vector3<f64>) -> SideDirection {
+    match round_vector(normal) {
+        (0, 0, 1) => SideDirection::Top,
+        (0, 0, -1) => SideDirection::Bottom,
+        (1, 0, 0) => SideDirection::Right,
+        (-1, 0, 0) => SideDirection::Left,
+        (0, 1, 0) => SideDirection::Front,
+        (0, -1, 0) => SideDirection::Back,
+        _ => panic!("unexpected normal {:?}", normal)
     }
 }
 
-fn transform_direction(direction: Direction, transformation: &Transformation) -> Direction {
-    let mut result = direction;
-    for _ in 0..transformation.rotation_count {
-        result = match result {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        };
+// Folds the squares connected to current_side onto the cube, or gives the top left corner of a square which
+// lands on a side another square already covers, which happens when the board isn't a cube net
+fn _calc_cube(board: &Board, sides: &mut HashMap<SideDirection, Side>, current_side: Side, len_of_side: i64) -> Result<(), Coord> {
+    if sides.contains_key(&current_side.direction) {
+        return Err(current_side.coord);
     }
+    sides.insert(current_side.direction, current_side);
 
-    if transformation.flip_row {
-        result = match result {
-            Direction::Up => Direction::Down,
-            Direction::Right => Direction::Right,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Left
+    // folding a neighboring side down over an edge is a quarter turn around the other axis
+    for (increment, axis, angle) in [
+        (Increment { row: 0, col: 1 }, Vector3::y_axis(), FRAC_PI_2),
+        (Increment { row: 0, col: -1 }, Vector3::y_axis(), -FRAC_PI_2),
+        (Increment { row: 1, col: 0 }, Vector3::x_axis(), -FRAC_PI_2),
+        (Increment { row: -1, col: 0 }, Vector3::x_axis(), FRAC_PI_2),
+    ] {
+        let coord = Coord {
+            row: current_side.coord.row + increment.row * len_of_side,
+            col: current_side.coord.col + increment.col * len_of_side,
+        };
+        match board.get(coord) {
+            None | Some(' ') => continue,
+            Some(_) => {}
         }
-    };
-
-    if transformation.flip_col {
-        result = match result {
-            Direction::Up => Direction::Up,
-            Direction::Right => Direction::Left,
-            Direction::Down => Direction::Down,
-            Direction::Left => Direction::Right,
+        if sides.values().any(|side| side.coord == coord) {
+            continue;
         }
-    };
 
-    result
+        let quaternion = current_side.quaternion * UnitQuaternion::from_axis_angle(&axis, angle);
+        let direction = to_side_direction(quaternion * Vector3::z());
+        _calc_cube(board, sides, Side { coord, direction, quaternion }, len_of_side)?;
+    }
+    Ok(())
 }
 
-fn get_side(cube: &Cube, board: &Board, current: Position, increment: Increment) -> Position {
-    let len_of_side = (max(board.num_columns(), board.num_rows()) / 4) as i64;
+// Errors with the tile where the board stops being a cube net and what was expected there, or no tile when
+// the board is the wrong size altogether
+fn calc_cube(board: &Board) -> Result<Cube, (Option<Coord>, String)> {
+    let area = board.board.values().filter(|c| **c != ' ').count();
+    let len_of_side = ((area / 6) as f64).sqrt().round() as i64;
+    if len_of_side == 0 || (len_of_side * len_of_side * 6) as usize != area {
+        return Err((None, format!("a board of 6 squares of the same size to fold into a cube, not {} tiles", area)));
+    }
 
-    let original_side = lookup_side(cube, current.coord).unwrap();
-    let new_coord = Coord { row: current.coord.row + increment.row, col: current.coord.col + increment.col };
-    match lookup_side(cube, new_coord) {
-        Some(_side) => {
-            Position { coord: new_coord, direction: current.direction }
+    let start = calc_start_position(board).ok_or((None, "an open tile to start from".to_string()))?;
+    let mut sides = HashMap::new();
+    _calc_cube(board, &mut sides, Side {
+        coord: Coord {
+            row: start.coord.row - start.coord.row % len_of_side,
+            col: start.coord.col - start.coord.col % len_of_side,
         },
-        None => {
-            let new_side = match original_side {
-                SideDirection::Top => {
-                    match increment {
-                        Increment { row: 0, col: -1 } => SideDirection::Left,
-                        Increment { row: 0, col: 1 } => SideDirection::Right,
-                        Increment { row: -1, col: 0 } => SideDirection::Back,
-                        Increment { row: 1, col: 0 } => SideDirection::Front,
-                        _ => panic!("unexpected")
-                    }
-                },
-                _ => panic!("TODO")
-            };
-            let quaternion = cube.sides[&new_side].quaternion;
-            let adjusted_new_coord = transform( increment, &quaternion, len_of_side);
-
-            let new_direction = transform_direction(current.direction, &transformation);
-            let new_side_coords = cube.sides[&new_side].0;
-            Position {
-                coord: Coord {
-                    row: adjusted_new_coord.row + new_side_coords.row,
-                    col: adjusted_new_coord.col + new_side_coords.col,
-                },
-                direction: new_direction
+        direction: SideDirection::Top,
+        quaternion: UnitQuaternion::identity(),
+    }, len_of_side).map_err(|coord| (Some(coord), "a square which folds onto a side of the cube no other square covers".to_string()))?;
+
+    // with the right area, six whole squares cover every tile
+    for side in sides.values() {
+        for row in side.coord.row..side.coord.row + len_of_side {
+            for col in side.coord.col..side.coord.col + len_of_side {
+                let coord = Coord { row, col };
+                if !matches!(board.get(coord), Some('.') | Some('#')) {
+                    return Err((Some(coord), format!("a tile, the side of the cube at row {} column {} isn't a whole square", side.coord.row + 1, side.coord.col + 1)));
+                }
             }
         }
     }
+    if sides.len() != 6 {
+        let cut_off = board.board.coords().map(|(row, col)| Coord { row, col }).find(|coord| {
+            board.get(*coord) != Some(&' ') && lookup_side_in(&sides, len_of_side, *coord).is_none()
+        });
+        return Err((cut_off, "a tile joined to the rest of the cube".to_string()));
+    }
+
+    Ok(Cube { sides, len_of_side })
 }
 
-fn calc_next_col(cube: &Cube, board: &Board, current: Position, diff: i64, as_cube: bool) -> Position {
-    let mut new_coord = Coord {
-        col: current.coord.col as i64 + diff,
-        row: current.coord.row,
-    };
+fn lookup_side(cube: &Cube, coord: Coord) -> Option<Side> {
+    lookup_side_in(&cube.sides, cube.len_of_side, coord)
+}
 
-    if as_cube {
-        get_side(&cube, &board, current, Increment { row: 0, col: diff })
-    } else {
-        loop {
-            match board.get(new_coord) {
-                None => {
-                    new_coord.col = new_coord.col.rem_euclid(board.num_columns() as i64);
-                },
-                Some(' ') => {
-                    new_coord.col = (new_coord.col as i64 + diff);
-                },
-                Some(_) => {
-                    break;
-                }
-            }
-        }
-        Position {
-            direction: current.direction,
-            coord: new_coord,
+fn lookup_side_in(sides: &HashMap<SideDirection, Side>, len_of_side: i64, coord: Coord) -> Option<Side> {
+    sides.values().find_map(|side| {
+        if coord.row >= side.coord.row && coord.row < side.coord.row + len_of_side &&
+            coord.col >= side.coord.col && coord.col < side.coord.col + len_of_side {
+            Some(*side)
+        } else {
+            None
         }
+    })
+}
+
+fn get_side(cube: &Cube, current: Position, increment: Increment) -> Position {
+    let new_coord = Coord { row: current.coord.row + increment.row, col: current.coord.col + increment.col };
+    if lookup_side(cube, new_coord).is_some() {
+        // sides touching on the board also touch on the cube
+        return Position { coord: new_coord, direction: current.direction };
+    }
+
+    // Work in doubled coordinates centered on the cube, so every tile center is an integer
+    // point on the surface. Stepping over an edge moves half a tile onto the edge and then
+    // half a tile down the neighboring side, which is the side facing the way we were going.
+    let len_of_side = cube.len_of_side;
+    let original_side = lookup_side(cube, current.coord).unwrap();
+    let local = Vector3::new(
+        (2 * (current.coord.col - original_side.coord.col) - (len_of_side - 1)) as f64,
+        (2 * (current.coord.row - original_side.coord.row) - (len_of_side - 1)) as f64,
+        len_of_side as f64,
+    );
+    let step = original_side.quaternion * Vector3::new(increment.col as f64, increment.row as f64, 0f64);
+    let normal = original_side.quaternion * Vector3::z();
+    let point = original_side.quaternion * local + step - normal;
+
+    let new_side = cube.sides[&to_side_direction(step)];
+    let inverse = new_side.quaternion.inverse();
+    let (x, y, _) = round_vector(inverse * point);
+    let (col_increment, row_increment, _) = round_vector(inverse * -normal);
+
+    Position {
+        coord: Coord {
+            row: new_side.coord.row + (y + len_of_side - 1) / 2,
+            col: new_side.coord.col + (x + len_of_side - 1) / 2,
+        },
+        direction: to_direction(Increment { row: row_increment, col: col_increment }),
     }
 }
 
-fn calc_next_row(cube: &Cube, board: &Board, current: Position, diff: i64, as_cube: bool) -> Position {
+fn wrap_around(board: &Board, current: Position, increment: Increment) -> Position {
     let mut new_coord = Coord {
-        col: current.coord.col,
-        row: current.coord.row as i64 + diff,
+        row: current.coord.row + increment.row,
+        col: current.coord.col + increment.col,
     };
 
-    if as_cube {
-        get_side(cube, board, current, Increment { col: 0, row: diff })
-    } else {
-        loop {
-            match board.get(new_coord) {
-                None => {
-                    new_coord.row = new_coord.row.rem_euclid(board.num_rows() as i64);
-                },
-                Some(' ') => {
-                    new_coord.row = (new_coord.row as i64 + diff);
-                },
-                Some(_) => {
-                    break;
-                }
+    loop {
+        match board.get(new_coord) {
+            None => {
+                new_coord.row = new_coord.row.rem_euclid(board.num_rows() as i64);
+                new_coord.col = new_coord.col.rem_euclid(board.num_columns() as i64);
+            },
+            Some(' ') => {
+                new_coord.row += increment.row;
+                new_coord.col += increment.col;
+            },
+            Some(_) => {
+                break;
             }
         }
-        Position {
-            direction: current.direction,
-            coord: new_coord,
-        }
+    }
+
+    Position {
+        direction: current.direction,
+        coord: new_coord,
     }
 }
 
-fn calc_next(cube: &Cube, board: &Board, current: Position, as_cube: bool) -> Option<Position> {
-    let next = match current.direction {
-        Direction::Left => {
-            calc_next_col(cube, board, current, -1, as_cube)
-        },
-        Direction::Up => {
-            calc_next_row(cube, board, current, -1, as_cube)
-        },
-        Direction::Right => {
-            calc_next_col(cube, board, current, 1, as_cube)
-        },
-        Direction::Down => {
-            calc_next_row(cube, board, current, 1, as_cube)
-        }
+fn calc_next(cube: Option<&Cube>, board: &Board, current: Position) -> Option<Position> {
+    let increment = to_increment(current.direction);
+    let next = match cube {
+        Some(_cube) => get_side(_cube, current, increment),
+        None => wrap_around(board, current, increment),
     };
 
     match board.get(next.coord) {
@@ -351,19 +362,17 @@ fn calc_next(cube: &Cube, board: &Board, current: Position, as_cube: bool) -> Op
         Some('#') => {
             None
         },
-        Some(' ') => panic!("unexpected"),
-        None => panic!("unexpected"),
-        _ => panic!("unexpected")
+        _ => panic!("unexpected position {:?}", next)
     }
 }
 
-fn follow_path(cube: &Cube, board: &Board, path: &Path, start: Position, as_cube: bool) -> Position {
+fn follow_path(cube: Option<&Cube>, board: &Board, path: &Path, start: Position) -> Position {
     let mut current = start;
     for _move in path.iter() {
         match _move {
             Move::Forward(steps) => {
                 for _ in 0..*steps {
-                    match calc_next(&cube, board, current, as_cube) {
+                    match calc_next(cube, board, current) {
                         None => {
                             break;
                         },
@@ -402,64 +411,132 @@ fn follow_path(cube: &Cube, board: &Board, path: &Path, start: Position, as_cube
     current
 }
 
-fn calc_start_position(board: &Board) -> Position {
-    for (row, col) in board.board.indices_row_major() {
-        let coord = Coord { row: row as i64, col: col as i64 };
-        match board.get(coord) {
-            Some('.') => {
-                return Position {
-                    coord,
-                    direction: Direction::Right,
-                };
-            },
-            Some(_) => {
-                // skip
-            },
-            None => {
-                panic!("unexpected")
-            }
-        }
-    }
-
-    panic!("could not find start position");
+fn calc_start_position(board: &Board) -> Option<Position> {
+    board.board.coords().map(|(row, col)| Coord { row, col }).find(|coord| board.get(*coord) == Some(&'.')).map(|coord| Position {
+        coord,
+        direction: Direction::Right,
+    })
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let (board, path) = parse(file_path);
-    let cube = calc_cube(&board);
-
-
-    let ending = follow_path(&cube, &board, &path, calc_start_position(&board), false);
-    let ret = (1000 * (ending.coord.row + 1)) + (4 * (ending.coord.col + 1)) + match ending.direction {
+fn calc_password(ending: Position) -> i64 {
+    (1000 * (ending.coord.row + 1)) + (4 * (ending.coord.col + 1)) + match ending.direction {
         Direction::Right => 0,
         Direction::Down => 1,
         Direction::Left => 2,
         Direction::Up => 3,
-    };
+    }
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "        ...#\n",
+        "        .#..\n",
+        "        #...\n",
+        "        ....\n",
+        "...#.......#\n",
+        "........#...\n",
+        "..#....#....\n",
+        "..........#.\n",
+        "        ...#....\n",
+        "        .....#..\n",
+        "        .#......\n",
+        "        ......#.\n",
+        "\n",
+        "10R5L5R10L4R5L5\n",
+    );
+    let (board, path) = parse(contents).unwrap();
+    let start = calc_start_position(&board).unwrap();
+    assert_eq!(calc_password(follow_path(None, &board, &path, start)), 6032);
 
-    ret as i64
+    let cube = calc_cube(&board).unwrap();
+    assert_eq!(cube.len_of_side, 4);
+    assert_eq!(calc_password(follow_path(Some(&cube), &board, &path, start)), 5031);
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let (board, path) = parse(file_path);
-    let cube = calc_cube(&board);
+#[test]
+fn test_cube_nets() {
+    // walking 4 sides in a straight line around an empty cube should end up where it started
+    let len_of_side = 3;
+    // all 11 nets: six with a row of 4, three with a row of 3 and a pair, and the two rows of 3 and the staircase
+    for net in [
+        vec!["#", "####", "#"],
+        vec!["#", "####", " #"],
+        vec!["#", "####", "  #"],
+        vec![" #", "####", " #"],
+        vec!["  #", "###", "  ##"],
+        vec![" ##", " #", "##", "#"],
+        vec!["#", "####", "   #"],
+        vec![" #", "####", "  #"],
+        vec!["##", " ###", "  #"],
+        vec!["##", " ##", "  ##"],
+        vec!["###", "  ###"],
+    ] {
+        let mut contents = String::new();
+        for net_row in net.iter() {
+            for _ in 0..len_of_side {
+                for c in net_row.chars() {
+                    contents.push_str(&c.to_string().replace('#', ".").repeat(len_of_side));
+                }
+                contents.push('\n');
+            }
+        }
+        contents.push_str("\n1\n");
+
+        let (board, _) = parse(&contents).unwrap();
+        let cube = calc_cube(&board).unwrap();
+        for (row, col) in board.board.coords() {
+            let coord = Coord { row, col };
+            if board.get(coord) != Some(&'.') {
+                continue;
+            }
 
-    let ending = follow_path(&cube, &board, &path, calc_start_position(&board), true);
-    let ret = (1000 * (ending.coord.row + 1)) + (4 * (ending.coord.col + 1)) + match ending.direction {
-        Direction::Right => 0,
-        Direction::Down => 1,
-        Direction::Left => 2,
-        Direction::Up => 3,
-    };
+            for direction in [Direction::Right, Direction::Down, Direction::Left, Direction::Up] {
+                let start = Position { coord, direction };
+                let path = vec![Move::Forward(4 * len_of_side)];
+                let ending = follow_path(Some(&cube), &board, &path, start);
+                assert_eq!((ending.coord, ending.direction), (start.coord, start.direction), "net {:?}", net);
+            }
+        }
+    }
+
+    // folding a rectangle puts two squares on the same side, and a square on its own can't be reached from the start
+    for (contents, line, column) in [("...\n...\n\n1\n", 2, 2), (". ...\n  .\n  .\n\n1\n", 1, 3)] {
+        let (board, _) = parse(contents).unwrap();
+        assert_eq!(board.cube().err().map(|err| (err.line, err.column)), Some((line, column)));
+    }
+}
 
-    ret as i64
+pub struct Day22;
 
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+    type Input = (Board, Path);
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse(contents)
+    }
+
+    fn part1((board, path): &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(board, path).into())
+    }
+
+    fn part2((board, path): &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(board, path)?.into())
+    }
 }
-*/
-pub fn part1(file_path: &str) -> i64 {
-    0
+
+// parse only makes boards which have somewhere to start
+
+pub fn part1(board: &Board, path: &Path) -> i64 {
+    let ending = follow_path(None, board, path, calc_start_position(board).unwrap());
+    calc_password(ending)
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    0
+pub fn part2(board: &Board, path: &Path) -> Result<i64, ParseError> {
+    let cube = board.cube()?;
+
+    let ending = follow_path(Some(&cube), board, path, calc_start_position(board).unwrap());
+    Ok(calc_password(ending))
 }
@@ -1,107 +1,14 @@
-use std::cmp::{max, min};
 use std::collections::{HashSet, VecDeque};
-use array2d::{Array2D, Error};
 use itertools::Itertools;
-use crate::common::get_trimmed_lines;
-
-
-type CoordType = (i64, i64);
-
-struct AdjBoard<T> {
-    board: Array2D<T>,
-    mins: CoordType,
-}
-
-impl<T> AdjBoard<T> {
-    fn indices_row_major(&self) -> impl DoubleEndedIterator<Item = CoordType> + Clone + '_ {
-        self.board.indices_row_major().map(|coord| {
-            (coord.0 as i64 + self.mins.0 as i64, coord.1 as i64 + self.mins.1 as i64)
-        })
-    }
-
-    fn row_len(&self) -> i64 {
-        self.board.row_len() as i64
-    }
-
-    fn column_len(&self) -> i64 {
-        self.board.column_len() as i64
-    }
-}
-
-impl AdjBoard<char> {
-    pub fn filled_with(elem: char, mins: CoordType, maxes: CoordType) -> AdjBoard<char> {
-
-        AdjBoard {
-            board: Array2D::filled_with(elem, (maxes.0 + 1 - mins.0) as usize, (maxes.1 + 1 - mins.1) as usize),
-            mins
-        }
-    }
-
-    fn get(&self, i: CoordType) -> char {
-        let row = (i.0 as i64 - self.mins.0 as i64);
-        let col = (i.1 as i64 - self.mins.1 as i64);
-        if row >= 0 && row < self.board.num_rows() as i64 && col >= 0 && col < self.board.num_columns() as i64 {
-            match self.board.get(row as usize, col as usize) {
-                Some(_val) => {
-                    *_val
-                },
-                None => {
-                    '.'
-                }
-            }
-        } else {
-            '.'
-        }
-    }
-
-    fn set(&mut self, i: CoordType, elem: char) {
-        let row = (i.0 as i64 - self.mins.0 as i64);
-        let col = (i.1 as i64 - self.mins.1 as i64);
-
-        let ret = {
-            if row >= 0 && row < self.board.num_rows() as i64 && col >= 0 && col < self.board.num_columns() as i64 {
-                self.board.set(row as usize, col as usize, elem)
-            } else {
-                Err(Error::IndicesOutOfBounds(row as usize, col as usize))
-            }
-        };
-        match ret {
-            Ok(_) => (),
-            Error => {
-                let mins = (
-                    min(self.mins.0, i.0),
-                    min(self.mins.1, i.1),
-                );
-
-                let maxes = (
-                    max(self.board.num_rows() as i64 - 1 + self.mins.0, i.0),
-                    max(self.board.num_columns() as i64 - 1 + self.mins.1, i.1)
-                );
-
-                let mut new_board = AdjBoard::filled_with('.', mins, maxes);
-                let indices: Vec<_> = new_board.indices_row_major().collect();
-                for coord in indices {
-                    let val =
-                        if coord == i {
-                            elem
-                        } else {
-                            self.get(coord)
-                        };
-
-                    if val != '.' {
-                        new_board.set(coord, val);
-                    }
-                }
-                self.board = new_board.board;
-                self.mins = new_board.mins;
-            }
-        }
-    }
-}
+use log::trace;
+use crate::animate::{no_frames, Frame, OnFrame};
+use crate::common::{get_trimmed_lines, Coord, Grid, NEIGHBORS_8};
+use crate::error::ParseError;
+use crate::solution::{Answer, Part, Solution};
 
 #[test]
 fn test_board() {
-    let mut board = AdjBoard::filled_with('.', (0, 0), (1, 1));
+    let mut board = Grid::filled_with('.', (0, 0), (1, 1));
 
     const VALS: [(i64, i64); 6] = [
         (0, 0),
@@ -113,49 +20,42 @@ fn test_board() {
     ];
 
     for val in VALS.iter() {
-        assert_eq!(board.get(*val), '.');
+        assert_eq!(*board.get_or_fill(*val), '.');
     }
 
     for (i, val) in VALS.iter().enumerate() {
-        board.set(*val, i.to_string().chars().nth(0).unwrap());
+        board.set(*val, i.to_string().chars().next().unwrap());
     }
 
     for (i, val) in VALS.iter().enumerate() {
-        assert_eq!(board.get(*val), i.to_string().chars().nth(0).unwrap());
+        assert_eq!(*board.get_or_fill(*val), i.to_string().chars().next().unwrap());
     }
 
-    assert_eq!(board.mins, (-4, -4));
-    assert_eq!(board.indices_row_major().min_by_key(|c| c.0).unwrap().0, -4);
-    assert_eq!(board.indices_row_major().max_by_key(|c| c.0).unwrap().0, 4);
-    assert_eq!(board.indices_row_major().min_by_key(|c| c.1).unwrap().1, -4);
-    assert_eq!(board.indices_row_major().max_by_key(|c| c.1).unwrap().1, 4);
+    assert_eq!(board.mins(), (-4, -4));
+    assert_eq!(board.coords().min_by_key(|c| c.0).unwrap().0, -4);
+    assert_eq!(board.coords().max_by_key(|c| c.0).unwrap().0, 4);
+    assert_eq!(board.coords().min_by_key(|c| c.1).unwrap().1, -4);
+    assert_eq!(board.coords().max_by_key(|c| c.1).unwrap().1, 4);
 
-    print_board(&board);
+    println!("{}", render_board(&board));
 }
 
 
-fn read_board(file_path: &str) -> AdjBoard<char> {
-    let rows: Vec<Vec<char>> = get_trimmed_lines(file_path).iter().map(|line| {
-        let cs: Vec<char> = line.chars().collect();
-        cs
-    }).collect();
-    let array2d = Array2D::from_rows(&rows).unwrap();
-
-    AdjBoard {
-        board: array2d,
-        mins: (0, 0)
-    }
+pub fn read_board(contents: &str) -> Result<Grid<char>, ParseError> {
+    let lines = get_trimmed_lines(contents);
+    Grid::parse(&lines, '.', |c| if c == '#' || c == '.' { Some(c) } else { None }, "an elf '#' or empty ground '.'")
 }
 
-fn print_board(board: &AdjBoard<char>) {
-    let row_range= -3..board.row_len() + 3;
+fn render_board(board: &Grid<char>) -> String {
+    let mut rendered = String::new();
+    let row_range= -3..board.num_rows() as i64 + 3;
 
     for row in row_range {
-        let col_range = -3..board.column_len() + 3;
+        let col_range = -3..board.num_columns() as i64 + 3;
 
         for col in col_range {
-            let coord = (row + board.mins.0, col + board.mins.1);
-            let piece = board.get(coord);
+            let coord = (row + board.mins().0, col + board.mins().1);
+            let piece = *board.get_or_fill(coord);
             let c = if piece != '.' {
                 piece
             } else if coord.0 == 0 && coord.1 == 0 {
@@ -167,13 +67,20 @@ fn print_board(board: &AdjBoard<char>) {
             } else {
                 '.'
             };
-            print!("{}", c);
+            rendered.push(c);
         }
-        println!();
+        rendered.push('\n');
     }
 
-    println!();
-    println!();
+    rendered
+}
+
+fn elves_frame(board: &Grid<char>, round: i64) -> Frame {
+    Frame {
+        grid: board.clone(),
+        focus: None,
+        caption: if round == 0 { "Initial state".to_string() } else { format!("End of round {}", round) },
+    }
 }
 
 enum ElfDirection {
@@ -185,32 +92,25 @@ enum ElfDirection {
 
 #[derive(Debug, Copy, Clone)]
 struct Elf {
-    coord: CoordType,
-    elfnum: usize,
+    coord: Coord,
 }
 
-fn calc_moves(board: &AdjBoard<char>, directions: &VecDeque<ElfDirection>) -> Vec<(Elf, CoordType)> {
-    let elves: Vec<Elf> = board.indices_row_major().filter(|coord| {
-        board.get(*coord) == '#'
-    }).enumerate().map(|(elfnum, coord)| { Elf{ coord, elfnum }}).collect();
+fn calc_moves(board: &Grid<char>, directions: &VecDeque<ElfDirection>) -> Vec<(Elf, Coord)> {
+    let elves: Vec<Elf> = board.coords().filter(|coord| {
+        *board.get_or_fill(*coord) == '#'
+    }).map(|coord| { Elf{ coord }}).collect();
 
     let unsurrounded_elves: Vec<Elf> = elves.iter().filter_map(|elf| {
-        for diff_row in [-1, 0, 1] {
-            for diff_col in [-1, 0, 1] {
-                let adjacent_coord = (elf.coord.0 + diff_row, elf.coord.1 + diff_col);
-                if adjacent_coord == elf.coord {
-                    continue;
-                }
-
-                if board.get(adjacent_coord) == '#' {
-                    return Some(*elf);
-                }
+        for (diff_row, diff_col) in NEIGHBORS_8 {
+            let adjacent_coord = (elf.coord.0 + diff_row, elf.coord.1 + diff_col);
+            if *board.get_or_fill(adjacent_coord) == '#' {
+                return Some(*elf);
             }
         }
         None
     }).collect();
 
-    let proposals: Vec<(Elf, CoordType)> = unsurrounded_elves.iter().filter_map(|elf| {
+    let proposals: Vec<(Elf, Coord)> = unsurrounded_elves.iter().filter_map(|elf| {
         let other_elf = directions.iter().find_map(|direction| {
             let to_check: [(i64, i64); 3] = match *direction {
                 ElfDirection::North => [(-1, -1), (-1, 0), (-1, 1)],
@@ -221,7 +121,7 @@ fn calc_moves(board: &AdjBoard<char>, directions: &VecDeque<ElfDirection>) -> Ve
 
             let found_other = to_check.iter().find_map(|(other_row, other_col)| {
                 let other_coord = (elf.coord.0 + other_row, elf.coord.1 + other_col);
-                match board.get(other_coord) {
+                match board.get_or_fill(other_coord) {
                     '#' => Some(other_coord),
                     _ => None
                 }
@@ -242,9 +142,9 @@ fn calc_moves(board: &AdjBoard<char>, directions: &VecDeque<ElfDirection>) -> Ve
         other_elf
     }).collect();
 
-    let dests: HashSet<CoordType> = proposals.iter().sorted_by_key(|(src, dest)| {
+    let dests: HashSet<Coord> = proposals.iter().sorted_by_key(|(_src, dest)| {
         dest
-    }).group_by(|(src, dest)| {
+    }).group_by(|(_src, dest)| {
         *dest
     }).into_iter().filter_map(|(key, group)| {
         if group.count() == 1 {
@@ -254,7 +154,7 @@ fn calc_moves(board: &AdjBoard<char>, directions: &VecDeque<ElfDirection>) -> Ve
         }
     }).collect();
 
-    let valid_proposals: Vec<(Elf, CoordType)> = proposals.iter().filter_map(|(src, dest)| {
+    let valid_proposals: Vec<(Elf, Coord)> = proposals.iter().filter_map(|(src, dest)| {
         if dests.contains(dest) {
             Some((*src, *dest))
         } else {
@@ -265,14 +165,14 @@ fn calc_moves(board: &AdjBoard<char>, directions: &VecDeque<ElfDirection>) -> Ve
     valid_proposals
 }
 
-fn simulate_round(board: &mut AdjBoard<char>, directions: &mut VecDeque<ElfDirection>) -> Vec<(Elf, CoordType)> {
-    let valid_proposals = calc_moves(&board, &directions);
+fn simulate_round(board: &mut Grid<char>, directions: &mut VecDeque<ElfDirection>) -> Vec<(Elf, Coord)> {
+    let valid_proposals = calc_moves(board, directions);
 
-    for (src, dest) in valid_proposals.iter() {
+    for (src, _dest) in valid_proposals.iter() {
         board.set(src.coord, '.');
     }
 
-    for (src, dest) in valid_proposals.iter() {
+    for (_src, dest) in valid_proposals.iter() {
         board.set(*dest, '#');
     }
 
@@ -281,21 +181,54 @@ fn simulate_round(board: &mut AdjBoard<char>, directions: &mut VecDeque<ElfDirec
     valid_proposals
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let mut board = read_board(file_path);
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+    const ANIMATED: bool = true;
+    type Input = Grid<char>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_board(contents)
+    }
+
+    fn part1(board: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(board).into())
+    }
+
+    fn part2(board: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(board).into())
+    }
+
+    fn animate(board: &Self::Input, _config: &Self::Config, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(match part {
+            Part::One => spread_out(board, on_frame),
+            Part::Two => spread_out_until_still(board, on_frame),
+        }.into()))
+    }
+}
+
+pub fn part1(board: &Grid<char>) -> i64 {
+    spread_out(board, &mut no_frames)
+}
+
+fn spread_out(board: &Grid<char>, on_frame: &mut OnFrame) -> i64 {
+    let mut board = board.clone();
 
     let mut directions: VecDeque<ElfDirection> = VecDeque::from([ElfDirection::North, ElfDirection::South, ElfDirection::West, ElfDirection::East]);
 
-    print_board(&board);
+    trace!("Initial state\n{}", render_board(&board));
+    on_frame(&|| elves_frame(&board, 0));
     for round in 0..10 {
         simulate_round(&mut board, &mut directions);
 
-        println!("End of round {}", round + 1);
-        print_board(&board);
+        trace!("End of round {}\n{}", round + 1, render_board(&board));
+        on_frame(&|| elves_frame(&board, round + 1));
     }
 
-    let elfcoords: Vec<CoordType> = board.indices_row_major().filter(|coord| {
-        board.get(*coord) == '#'
+    let elfcoords: Vec<Coord> = board.coords().filter(|coord| {
+        *board.get_or_fill(*coord) == '#'
     }).collect();
     let mins = (
         elfcoords.iter().min_by_key(|coord| coord.0).unwrap().0,
@@ -309,7 +242,7 @@ pub fn part1(file_path: &str) -> i64 {
     let mut count_empty = 0;
     for row in mins.0..(maxs.0 + 1) {
         for col in mins.1..(maxs.1 + 1) {
-            if board.get((row, col)) == '.' {
+            if *board.get_or_fill((row, col)) == '.' {
                 count_empty += 1;
             }
         }
@@ -318,23 +251,45 @@ pub fn part1(file_path: &str) -> i64 {
     count_empty
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let mut board = read_board(file_path);
+pub fn part2(board: &Grid<char>) -> i64 {
+    spread_out_until_still(board, &mut no_frames)
+}
+
+fn spread_out_until_still(board: &Grid<char>, on_frame: &mut OnFrame) -> i64 {
+    let mut board = board.clone();
 
     let mut directions: VecDeque<ElfDirection> = VecDeque::from([ElfDirection::North, ElfDirection::South, ElfDirection::West, ElfDirection::East]);
 
-    print_board(&board);
+    trace!("Initial state\n{}", render_board(&board));
+    on_frame(&|| elves_frame(&board, 0));
     let mut round = 0;
     loop {
         let moves = simulate_round(&mut board, &mut directions);
 
-        if moves.len() == 0 {
+        if moves.is_empty() {
             break;
         }
 
-        print_board(&board);
+        trace!("End of round {}\n{}", round + 1, render_board(&board));
         round += 1;
+        on_frame(&|| elves_frame(&board, round));
     }
 
     round + 1
 }
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "....#..\n",
+        "..###.#\n",
+        "#...#.#\n",
+        ".#...##\n",
+        "#.###..\n",
+        "##.#.##\n",
+        ".#..#..\n",
+    );
+    let board = Day23::parse(contents).unwrap();
+    assert_eq!(part1(&board), 110);
+    assert_eq!(part2(&board), 20);
+}
@@ -1,76 +1,66 @@
-use std::collections::HashSet;
-use std::rc::Rc;
-use array2d::Array2D;
-use crate::common::get_trimmed_lines;
-
-fn parse_board(file_path: &str) -> Board {
-    let rows: Vec<Vec<char>> = get_trimmed_lines(file_path).iter().map(|line| {
-        let chars: Vec<char> = line.chars().collect();
-        chars
-    }).collect();
-
-    let board = Array2D::from_rows(&rows).unwrap();
+use std::mem;
+use log::trace;
+use crate::animate::{no_frames, Frame, OnFrame};
+use crate::common::{a_star, get_trimmed_lines, manhattan_distance, Coord, Grid};
+use crate::error::ParseError;
+use crate::solution::{Answer, Part, Solution};
+
+pub fn parse_board(contents: &str) -> Result<Board, ParseError> {
+    let lines = get_trimmed_lines(contents);
+    let board = Grid::parse(&lines, '#', |c| if "#.<>^v".contains(c) { Some(c) } else { None }, "a wall '#', ground '.' or a blizzard <, >, ^ or v")?;
+    if board.num_rows() < 3 || board.num_columns() < 3 {
+        return Err(ParseError::new(1, 1, "", "a valley surrounded by walls"));
+    }
+    if board.position(|c| *c != '#').is_none() {
+        return Err(lines[0].error("a gap in the walls to start from"));
+    }
 
-    let blizzards = board.indices_row_major().filter_map(|(row, col)| {
-        let c = board[(row, col)];
+    let blizzards = board.iter().filter_map(|(loc, c)| {
         match c {
-            'v' => Some(Blizzard { direction: (1, 0), loc: (row, col) }),
-            '^' => Some(Blizzard { direction: (-1, 0), loc: (row, col) }),
-            '<' => Some(Blizzard { direction: (0, -1), loc: (row, col) }),
-            '>' => Some(Blizzard { direction: (0, 1), loc: (row, col) }),
+            'v' => Some(Blizzard { direction: (1, 0), loc }),
+            '^' => Some(Blizzard { direction: (-1, 0), loc }),
+            '<' => Some(Blizzard { direction: (0, -1), loc }),
+            '>' => Some(Blizzard { direction: (0, 1), loc }),
             _ => None
         }
     }).collect();
-    Board {
+    Ok(Board {
         board,
         blizzards
-    }
+    })
 }
 
-fn print_board(board: &Board) {
-    for row in 0..board.board.num_rows() {
-        for col in 0..board.board.num_columns() {
-            print!("{}", board.board[(row, col)]);
-        }
-        println!();
-    }
-    println!();
-    println!();
+fn render_board(board: &Board) -> String {
+    board.board.to_string()
 }
 
-type Coord = (usize, usize);
-
+#[derive(Clone)]
 struct Blizzard {
     loc: Coord,
     direction: (i64, i64)
 }
 
-struct Board {
-    board: Array2D<char>,
+#[derive(Clone)]
+pub struct Board {
+    board: Grid<char>,
     blizzards: Vec<Blizzard>
 }
 
 fn move_board(board: &Board) -> Board {
-    let mut new_board = Array2D::filled_with('.', board.board.num_rows(), board.board.num_columns());
-
-    for row in 0..board.board.num_rows() {
-        for col in 0..board.board.num_columns() {
-            let c = if row < 1 || row >= board.board.num_rows() - 1 {
-                board.board[(row, col)]
-            } else if col < 1 || col >= board.board.num_columns() - 1 {
-                board.board[(row, col)]
-            } else {
-                '.'
-            };
-
-            new_board[(row, col)] = c;
+    let num_rows = board.board.num_rows() as i64;
+    let num_columns = board.board.num_columns() as i64;
+    let mut new_board = Grid::filled_with('.', (0, 0), (num_rows - 1, num_columns - 1));
+
+    for (row, col) in board.board.coords() {
+        if row < 1 || row >= num_rows - 1 || col < 1 || col >= num_columns - 1 {
+            new_board[(row, col)] = board.board[(row, col)];
         }
     }
 
     let new_blizzards: Vec<_> = board.blizzards.iter().map(|blizzard| {
         let loc = (
-            (((blizzard.loc.0 as i64 - 1) + blizzard.direction.0).rem_euclid(board.board.num_rows() as i64 - 2) + 1) as usize,
-            (((blizzard.loc.1 as i64 - 1) + blizzard.direction.1).rem_euclid(board.board.num_columns() as i64 - 2) + 1) as usize
+            ((blizzard.loc.0 - 1) + blizzard.direction.0).rem_euclid(num_rows - 2) + 1,
+            ((blizzard.loc.1 - 1) + blizzard.direction.1).rem_euclid(num_columns - 2) + 1
         );
         Blizzard {
             loc,
@@ -92,8 +82,8 @@ fn move_board(board: &Board) -> Board {
             '.' => c,
             'v'|'^'|'<'|'>' => '2',
             '2'|'3'|'4'|'5'|'6'|'7'|'8' => {
-                let old_digit = (old_val.to_digit(10).unwrap() + 1);
-                old_digit.to_string().chars().nth(0).unwrap()
+                let old_digit = old_val.to_digit(10).unwrap() + 1 ;
+                old_digit.to_string().chars().next().unwrap()
             },
             _ => panic!("unexpected")
 
@@ -107,81 +97,142 @@ fn move_board(board: &Board) -> Board {
 }
 
 fn find_start_and_end(board: &Board) -> (Coord, Coord) {
-    let start = (0..board.board.num_columns()).find_map(|col| {
-        if board.board[(0, col)] != '#' {
-            Some((0, col))
-        } else {
-            None
-        }
-    }).unwrap();
-
-    let end = (0..board.board.num_columns()).find_map(|col| {
-        if board.board[(board.board.num_rows() - 1, col)] != '#' {
-            Some((board.board.num_rows() - 1, col))
-        } else {
-            None
-        }
-    }).unwrap();
+    let start = board.board.position(|c| *c != '#').unwrap();
+    let end = board.board.iter().rev().find(|(_coord, c)| **c != '#').map(|(coord, _c)| coord).unwrap();
 
     (start, end)
 }
 
-fn find_minimum_path(board: Rc<Board>, start: Coord, end: Coord) -> (Rc<Board>, usize) {
-    let mut current_board: Rc<Board> = board;
-    let mut old_moves: HashSet<Coord> = [start].iter().map(|c| *c).collect();
-    let mut minutes = 0;
-    loop {
-        let next_board = Rc::new(move_board(&current_board));
-
-        let new_moves: HashSet<_> = old_moves.iter().map(|old_move| {
-            let possibilities: Vec<Coord> = [
-                (0, 0), (0, -1), (0, 1), (-1, 0), (1, 0)
-            ].iter().filter_map(|possibility| {
-                let new_coord = ((possibility.0 as i64 + old_move.0 as i64), (possibility.1 as i64 + old_move.1 as i64));
-
-                if new_coord.0 < 0 || new_coord.0 >= next_board.board.num_rows() as i64 || new_coord.1 < 0 || new_coord.1 >= next_board.board.num_columns() as i64 {
-                    return None;
-                }
-
-                if next_board.board[(new_coord.0 as usize, new_coord.1 as usize)] != '.' {
-                    return None;
-                }
-
-                Some((new_coord.0 as usize, new_coord.1 as usize))
-            }).collect();
-            possibilities
-        }).flatten().collect();
-
-        for _move in new_moves.iter() {
-            if *_move == end {
-                return (next_board, minutes + 1);
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// The blizzards are back where they started after this many minutes, so these are all the boards there are
+fn board_cycle(board: &Board) -> Vec<Grid<char>> {
+    let inner_rows = board.board.num_rows() - 2;
+    let inner_columns = board.board.num_columns() - 2;
+    let period = inner_rows / gcd(inner_rows, inner_columns) * inner_columns;
+
+    let mut boards = vec![board.board.clone()];
+    let mut current_board = board.clone();
+    while boards.len() < period {
+        current_board = move_board(&current_board);
+        trace!("minute {}\n{}", boards.len(), render_board(&current_board));
+        boards.push(current_board.board.clone());
+    }
+    boards
+}
+
+// Minutes to get from start to end when setting off at minute, and where the expedition is each minute.
+// None when the blizzards never leave a way through.
+fn find_minimum_path(boards: &[Grid<char>], start: Coord, end: Coord, minute: usize) -> Option<(usize, Vec<Coord>)> {
+    let period = boards.len();
+    let neighbors = |(coord, minute): &(Coord, usize)| {
+        let next_minute = (minute + 1) % period;
+        let next_board = &boards[next_minute];
+        let coord = *coord;
+        [
+            (0, 0), (0, -1), (0, 1), (-1, 0), (1, 0)
+        ].into_iter().filter_map(move |possibility| {
+            let new_coord = (possibility.0 + coord.0, possibility.1 + coord.1);
+
+            if next_board.get(new_coord) != Some(&'.') {
+                return None;
             }
-        }
 
-        println!("minute {} {}", minutes, new_moves.len());
-        print_board(&next_board);
-        minutes += 1;
-        old_moves = new_moves;
-        current_board = next_board.clone();
+            Some(((new_coord, next_minute), 1))
+        })
+    };
+
+    let (minutes, path) = a_star((start, minute % period), |(coord, _minute)| *coord == end, neighbors, |(coord, _minute)| manhattan_distance(*coord, end))?;
+    Some((minutes as usize, path.into_iter().map(|(coord, _minute)| coord).collect()))
+}
+
+fn expedition_frame(boards: &[Grid<char>], minute: usize, expedition: Coord) -> Frame {
+    let mut grid = boards[minute % boards.len()].clone();
+    grid[expedition] = 'E';
+    Frame {
+        grid,
+        focus: Some(expedition),
+        caption: format!("Minute {}", minute),
+    }
+}
+
+// Goes back and forth between the start and the end, returning the minutes it took
+fn cross_valley(board: &Board, trips: usize, on_frame: &mut OnFrame) -> Result<usize, ParseError> {
+    let (mut start, mut end) = find_start_and_end(board);
+    trace!("Initial state\n{}", render_board(board));
+
+    let boards = board_cycle(board);
+    let mut minute = 0;
+    on_frame(&|| expedition_frame(&boards, minute, start));
+    for trip in 0..trips {
+        let (minutes, path) = find_minimum_path(&boards, start, end, minute).ok_or_else(|| {
+            let goal = if trip % 2 == 0 { "to the end of the valley" } else { "back to the start of the valley" };
+            ParseError::new(0, 0, "", &format!("a way through the blizzards {} after minute {}", goal, minute))
+        })?;
+        for (step, expedition) in path.iter().enumerate().skip(1) {
+            on_frame(&|| expedition_frame(&boards, minute + step, *expedition));
+        }
+        minute += minutes;
+        mem::swap(&mut start, &mut end);
     }
+    Ok(minute)
 }
 
-pub fn part1(file_path: &str) -> i64 {
-    let board = parse_board(file_path);
-    let (start, end) = find_start_and_end(&board);
-    print_board(&board);
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+    const ANIMATED: bool = true;
+    type Input = Board;
+    type Config = ();
 
-    let (new_board, minutes) = find_minimum_path(Rc::new(board), start, end);
-    minutes as i64
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse_board(contents)
+    }
+
+    fn part1(board: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(board)?.into())
+    }
+
+    fn part2(board: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(board)?.into())
+    }
+
+    fn animate(board: &Self::Input, _config: &Self::Config, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        let trips = match part {
+            Part::One => 1,
+            // there, back for the snacks and there again
+            Part::Two => 3,
+        };
+        Some(cross_valley(board, trips, on_frame).map(|minutes| (minutes as i64).into()))
+    }
 }
 
-pub fn part2(file_path: &str) -> i64 {
-    let board = parse_board(file_path);
-    let (start, end) = find_start_and_end(&board);
-    print_board(&board);
+pub fn part1(board: &Board) -> Result<i64, ParseError> {
+    Ok(cross_valley(board, 1, &mut no_frames)? as i64)
+}
+
+pub fn part2(board: &Board) -> Result<i64, ParseError> {
+    Ok(cross_valley(board, 3, &mut no_frames)? as i64)
+}
 
-    let (end_1st_board, end_1st_minutes) = find_minimum_path(Rc::new(board), start, end);
-    let (start_2nd_board, start_2nd_minutes) = find_minimum_path(end_1st_board, end, start);
-    let (end_2nd_board, end_2nd_minutes) = find_minimum_path(start_2nd_board, start, end);
-    (end_1st_minutes + start_2nd_minutes + end_2nd_minutes) as i64
-}
\ No newline at end of file
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "#.######\n",
+        "#>>.<^<#\n",
+        "#.<..<<#\n",
+        "#>v.><>#\n",
+        "#<^v^^>#\n",
+        "######.#\n",
+    );
+    let board = Day24::parse(contents).unwrap();
+    assert_eq!(part1(&board), Ok(18));
+    assert_eq!(part2(&board), Ok(54));
+
+    // the blizzards going up and down the only column cover the way out every minute
+    let board = Day24::parse("#.#\n#^#\n#v#\n#.#\n").unwrap();
+    assert_eq!(part1(&board).unwrap_err().to_string(), "expected a way through the blizzards to the end of the valley after minute 0, found end of input");
+}
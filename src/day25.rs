@@ -1,6 +1,8 @@
 use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Part, Solution};
 
-fn parse_snafu(num_str: &str) -> i64 {
+pub fn parse_snafu(num_str: &str) -> i64 {
     let mut n = 0;
     for c in num_str.chars() {
         n *= 5;
@@ -14,7 +16,7 @@ fn parse_snafu(num_str: &str) -> i64 {
     n
 }
 
-fn output_snafu(n: i64) -> String {
+pub fn output_snafu(n: i64) -> String {
     let mut number = n;
 
     let mut snafu: Vec<char> = Vec::new();
@@ -68,9 +70,46 @@ fn test_snafu() {
     }
 }
 
-pub fn part1(file_path: &str) -> String {
-    let lines = get_trimmed_lines(file_path);
+pub struct Day25;
 
-    let sum = lines.iter().map(|line| parse_snafu(line)).sum();
-    output_snafu(sum)
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const PARTS: &'static [Part] = &[Part::One];
+    type Input = Vec<String>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        get_trimmed_lines(contents).iter().map(|line| {
+            if let Some(idx) = line.find(|c| !"012-=".contains(c)) {
+                return Err(line.error_at(&line[idx..], "a SNAFU digit 0, 1, 2, - or ="));
+            }
+            Ok(line.to_string())
+        }).collect()
+    }
+
+    fn part1(lines: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        let sum = lines.iter().map(|line| parse_snafu(line)).sum();
+        Ok(output_snafu(sum).into())
+    }
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "1=-0-2\n",
+        "12111\n",
+        "2=0=\n",
+        "21\n",
+        "2=01\n",
+        "111\n",
+        "20012\n",
+        "112\n",
+        "1=-1=\n",
+        "1-12\n",
+        "12\n",
+        "1=\n",
+        "122\n",
+    );
+    let lines = Day25::parse(contents).unwrap();
+    assert_eq!(output_snafu(lines.iter().map(|line| parse_snafu(line)).sum()), "2=-1=0");
 }
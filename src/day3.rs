@@ -1,8 +1,10 @@
-use std::fs;
 use std::collections::HashSet;
 use itertools::Itertools;
+use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-fn char_to_value(char: char) -> u32 {
+pub fn char_to_value(char: char) -> u32 {
     match char {
         'a'..='z' => ((char as u32) - ('a' as u32)) + 1,
         'A'..='Z' => ((char as u32) - ('A' as u32)) + 26 + 1,
@@ -10,11 +12,36 @@ fn char_to_value(char: char) -> u32 {
     }
 }
 
-pub fn part1(file_path: &str) -> u32 {
-    let contents = fs::read_to_string(file_path).unwrap();
+pub struct Day3;
 
-    let lines = contents.split("\n").map(|line| line.trim()).filter(|line| !line.is_empty());
-    return lines.map(|line| {
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    type Input = Vec<String>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        get_trimmed_lines(contents).iter().map(|line| {
+            if let Some((idx, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+                return Err(line.error_at(&line[idx..idx + c.len_utf8()], "an item letter a-z or A-Z"));
+            }
+            if !line.len().is_multiple_of(2) {
+                return Err(line.error("a rucksack with an even number of items"));
+            }
+            Ok(line.to_string())
+        }).collect()
+    }
+
+    fn part1(rucksacks: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(rucksacks).into())
+    }
+
+    fn part2(rucksacks: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(rucksacks).into())
+    }
+}
+
+pub fn part1(rucksacks: &[String]) -> u32 {
+    rucksacks.iter().map(|line| {
         let piece1 = &line[..line.len() / 2];
         let piece2 = &line[line.len() / 2..];
 
@@ -29,15 +56,11 @@ pub fn part1(file_path: &str) -> u32 {
         }
 
         panic!("unexpected {} {}", piece1, piece2);
-    }).map(char_to_value).sum();
+    }).map(char_to_value).sum()
 }
 
-pub fn part2(file_path: &str) -> u32 {
-    let contents = fs::read_to_string(file_path).unwrap();
-
-    let lines = contents.split("\n").map(|line| line.trim()).filter(|line| !line.is_empty());
-
-    lines.into_iter().chunks(3).into_iter().map(|chunks| {
+pub fn part2(rucksacks: &[String]) -> u32 {
+    rucksacks.iter().chunks(3).into_iter().map(|chunks| {
         chunks.map(|line| {
             let mut set = HashSet::new();
             for char in line.chars() {
@@ -56,4 +79,19 @@ pub fn part2(file_path: &str) -> u32 {
         }
         *set.iter().next().unwrap()
     }).map(char_to_value).sum()
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "vJrwpWtwJgWrhcsFMMfFFhFp\n",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n",
+        "PmmdzqPrVvPwwTWBwg\n",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n",
+        "ttgJtRGJQctTZtZT\n",
+        "CrZsJsPPZsGzwwsLwLmpwMDw\n",
+    );
+    let rucksacks = Day3::parse(contents).unwrap();
+    assert_eq!(part1(&rucksacks), 157);
+    assert_eq!(part2(&rucksacks), 70);
+}
@@ -1,26 +1,56 @@
-use std::fs;
+use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-fn fully_contains(inner: &Vec<u32>, outer: &Vec<u32>) -> bool {
+pub fn fully_contains(inner: &[u32], outer: &[u32]) -> bool {
     outer[0] <= inner[0] && outer[1] >= inner[1]
 }
 
-fn partly_contains(inner: &Vec<u32>, outer: &Vec<u32>) -> bool {
+pub fn partly_contains(inner: &[u32], outer: &[u32]) -> bool {
     (outer[0] <= inner[0] && inner[0] <= outer[1]) || (outer[0] <= inner[1] && inner[1] <= outer[1])
 }
 
-pub fn part1(file_path: &str) -> u64 {
-    let contents = fs::read_to_string(file_path).unwrap();
+pub struct Day4;
 
-    let lines = contents.split("\n").map(|line| line.trim()).filter(|line| !line.is_empty());
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    type Input = Vec<Vec<Vec<u32>>>;
+    type Config = ();
 
-    let pairs: Vec<Vec<Vec<u32>>> = lines.map(|line| {
-        line.split(",").map(|piece| {
-            piece.split("-").map(|number| {
-                number.parse::<u32>().unwrap()
-            }).collect()
-        }).collect()
-    }).collect();
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse_pairs(contents)
+    }
+
+    fn part1(pairs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(pairs).into())
+    }
+
+    fn part2(pairs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(pairs).into())
+    }
+}
+
+pub fn parse_pairs(contents: &str) -> Result<Vec<Vec<Vec<u32>>>, ParseError> {
+    let lines = get_trimmed_lines(contents);
+
+    lines.iter().map(|line| {
+        let pair: Vec<Vec<u32>> = line.split(',').map(|piece| {
+            let range: Vec<u32> = piece.split('-').map(|number| {
+                line.parse_at::<u32>(number, "a section number")
+            }).collect::<Result<_, _>>()?;
+            if range.len() != 2 {
+                return Err(line.error_at(piece, "a range like 2-4"));
+            }
+            Ok(range)
+        }).collect::<Result<_, _>>()?;
+        if pair.len() != 2 {
+            return Err(line.error("a pair of ranges like 2-4,6-8"));
+        }
+        Ok(pair)
+    }).collect()
+}
 
+pub fn part1(pairs: &[Vec<Vec<u32>>]) -> u64 {
     let mut fully_contain = 0;
     for pair in pairs {
         if fully_contains(&pair[0], &pair[1]) || fully_contains(&pair[1], &pair[0]) {
@@ -30,19 +60,7 @@ pub fn part1(file_path: &str) -> u64 {
     fully_contain
 }
 
-pub fn part2(file_path: &str) -> u64 {
-    let contents = fs::read_to_string(file_path).unwrap();
-
-    let lines = contents.split("\n").map(|line| line.trim()).filter(|line| !line.is_empty());
-
-    let pairs: Vec<Vec<Vec<u32>>> = lines.map(|line| {
-        line.split(",").map(|piece| {
-            piece.split("-").map(|number| {
-                number.parse::<u32>().unwrap()
-            }).collect()
-        }).collect()
-    }).collect();
-
+pub fn part2(pairs: &[Vec<Vec<u32>>]) -> u64 {
     let mut partly_contain = 0;
     for pair in pairs {
         if partly_contains(&pair[0], &pair[1]) || partly_contains(&pair[1], &pair[0]) {
@@ -50,4 +68,19 @@ pub fn part2(file_path: &str) -> u64 {
         }
     }
     partly_contain
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "2-4,6-8\n",
+        "2-3,4-5\n",
+        "5-7,7-9\n",
+        "2-8,3-7\n",
+        "6-6,4-6\n",
+        "2-6,4-8\n",
+    );
+    let pairs = Day4::parse(contents).unwrap();
+    assert_eq!(part1(&pairs), 2);
+    assert_eq!(part2(&pairs), 4);
+}
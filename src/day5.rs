@@ -1,10 +1,39 @@
-use std::fs;
 use itertools::Itertools;
+use crate::common::numbered_lines;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-pub fn puzzle(file_path: &str, part1: bool) -> String {
-    let contents = fs::read_to_string(file_path).unwrap();
+pub struct Day5;
 
-    let lines: Vec<&str> = contents.split("\n").map(|line| line.trim_end()).collect();
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    type Input = Instructions;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse_instructions(contents)
+    }
+
+    fn part1(instructions: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(puzzle(instructions, true).into())
+    }
+
+    fn part2(instructions: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(puzzle(instructions, false).into())
+    }
+}
+
+pub struct Instructions {
+    pub crates: Vec<Vec<char>>,
+    // (count, from, to)
+    pub moves: Vec<(usize, usize, usize)>,
+}
+
+pub fn parse_instructions(contents: &str) -> Result<Instructions, ParseError> {
+    let lines: Vec<_> = numbered_lines(contents).into_iter().map(|mut line| {
+        line.text = line.text.trim_end().to_string();
+        line
+    }).collect();
 
     let mut crates: Vec<Vec<char>> = Vec::new();
     let mut linebreak: Option<usize> = None;
@@ -14,9 +43,13 @@ pub fn puzzle(file_path: &str, part1: bool) -> String {
             break
         }
 
-        for col_num in 0..(line.len()/4) + 1 {
-            let c = line.chars().nth(col_num*4 + 1).unwrap();
-            if line.chars().nth(col_num*4).unwrap() == '['  {
+        let chars: Vec<char> = line.chars().collect();
+        for col_num in 0..(chars.len()/4) + 1 {
+            if chars.get(col_num*4) == Some(&'[')  {
+                let c = match chars.get(col_num*4 + 1) {
+                    Some(c) if c.is_ascii_alphabetic() => *c,
+                    _ => return Err(line.error_at(&line[col_num*4..], "a crate like [A]"))
+                };
                 while crates.len() <= col_num {
                     crates.push(Vec::new());
                 }
@@ -26,19 +59,49 @@ pub fn puzzle(file_path: &str, part1: bool) -> String {
         }
     }
 
-    for col_num in 0..crates.len() {
-        crates[col_num].reverse();
+    let linebreak = linebreak.ok_or_else(|| {
+        ParseError::new(lines.len() + 1, 1, "", "a blank line between the crates and the moves")
+    })?;
+
+    for column in crates.iter_mut() {
+        column.reverse();
     }
 
-    for line in &lines[linebreak.unwrap()+1..] {
+    // crates on each stack as the moves go, so none can take crates a stack doesn't have
+    let mut heights: Vec<usize> = crates.iter().map(|column| column.len()).collect();
+    let mut moves = Vec::new();
+    for line in &lines[linebreak+1..] {
         if line.trim().is_empty() {
             break
         }
         let pieces: Vec<&str> = line.split(' ').collect();
-        let count: usize = pieces[1].parse().unwrap();
-        let from: usize = pieces[3].parse().unwrap();
-        let to: usize = pieces[5].parse().unwrap();
+        if pieces.len() != 6 || pieces[0] != "move" || pieces[2] != "from" || pieces[4] != "to" {
+            return Err(line.error("a move like \"move 1 from 2 to 1\""));
+        }
+        let count: usize = line.parse_at(pieces[1], "a crate count")?;
+        let stack = |piece: &str| {
+            match line.parse_at::<usize>(piece, "a stack number")? {
+                number if (1..=crates.len()).contains(&number) => Ok(number),
+                _ => Err(line.error_at(piece, &format!("a stack number from 1 to {}", crates.len())))
+            }
+        };
+        let from = stack(pieces[3])?;
+        let to = stack(pieces[5])?;
+        if count > heights[from - 1] {
+            return Err(line.error_at(pieces[1], &format!("at most the number of crates left on stack {}, which is {}", from, heights[from - 1])));
+        }
+        heights[from - 1] -= count;
+        heights[to - 1] += count;
+        moves.push((count, from, to));
+    }
 
+    Ok(Instructions { crates, moves })
+}
+
+pub fn puzzle(instructions: &Instructions, part1: bool) -> String {
+    let mut crates = instructions.crates.clone();
+
+    for &(count, from, to) in instructions.moves.iter() {
         if part1 {
             for _ in 0..count {
                 let popped = crates[from - 1].pop().unwrap();
@@ -58,17 +121,28 @@ pub fn puzzle(file_path: &str, part1: bool) -> String {
         }
     }
 
-    let response = crates.iter().map(|column| {
-        column.last().unwrap()
+    // stacks which end up empty have nothing on top
+    let response = crates.iter().filter_map(|column| {
+        column.last()
     }).join("");
 
     response
 }
 
-pub fn part1(file_path: &str) -> String {
-    puzzle(file_path, true)
-}
-
-pub fn part2(file_path: &str) -> String {
-    puzzle(file_path, false)
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "    [D]    \n",
+        "[N] [C]    \n",
+        "[Z] [M] [P]\n",
+        " 1   2   3 \n",
+        "\n",
+        "move 1 from 2 to 1\n",
+        "move 3 from 1 to 3\n",
+        "move 2 from 2 to 1\n",
+        "move 1 from 1 to 2\n",
+    );
+    let instructions = Day5::parse(contents).unwrap();
+    assert_eq!(puzzle(&instructions, true), "CMZ");
+    assert_eq!(puzzle(&instructions, false), "MCD");
 }
@@ -1,8 +1,10 @@
 use std::collections::{HashSet, VecDeque};
-use std::fs;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 
-fn calc_position(contents: &str, size_of_marker: usize) -> usize {
+// None when there's no marker anywhere in the datastream
+pub fn calc_position(contents: &str, size_of_marker: usize) -> Option<usize> {
     let mut position: Option<usize> = None;
     let mut deque = VecDeque::new();
     for (i, c) in contents.chars().enumerate() {
@@ -18,20 +20,50 @@ fn calc_position(contents: &str, size_of_marker: usize) -> usize {
         }
         deque.pop_front().unwrap();
     }
-    position.unwrap()
+    position
 }
 
 
-pub fn part1(file_path: &str) -> usize {
-    let file_contents = fs::read_to_string(file_path).unwrap();
-    let contents = file_contents.trim();
+pub struct Day6;
 
-    calc_position(contents, 4)
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    type Input = String;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let contents = contents.trim();
+        if contents.is_empty() {
+            return Err(ParseError::new(1, 1, "", "a datastream"));
+        }
+        Ok(contents.to_string())
+    }
+
+    fn part1(contents: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(find_marker(contents, 4, "start of packet")?.into())
+    }
+
+    fn part2(contents: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(find_marker(contents, 14, "start of message")?.into())
+    }
 }
 
-pub fn part2(file_path: &str) -> usize {
-    let file_contents = fs::read_to_string(file_path).unwrap();
-    let contents = file_contents.trim();
+fn find_marker(contents: &str, size_of_marker: usize, marker: &str) -> Result<usize, ParseError> {
+    calc_position(contents, size_of_marker).ok_or_else(|| {
+        ParseError::new(1, contents.chars().count() + 1, "", &format!("{} different characters in a row for the {} marker", size_of_marker, marker))
+    })
+}
 
-    calc_position(contents, 14)
+#[test]
+fn test_example() {
+    for (contents, packet, message) in [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ] {
+        assert_eq!(calc_position(contents, 4), Some(packet));
+        assert_eq!(calc_position(contents, 14), Some(message));
+    }
 }
@@ -1,23 +1,73 @@
 use std::collections::HashMap;
-use std::fs;
 use itertools::Itertools;
+use log::{debug, trace};
+use crate::common::get_trimmed_lines;
+use crate::error::ParseError;
+use crate::params::{self, parse_value, ParamError};
+use crate::solution::{Answer, Solution};
 
-fn calc_directories(file_path: &str) -> HashMap<Vec<String>, usize> {
-    let file_contents = fs::read_to_string(file_path).unwrap();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub disk_size: usize,
+    pub needed_space: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            disk_size: 70000000,
+            needed_space: 30000000,
+        }
+    }
+}
+
+impl params::Config for Config {
+    const KEYS: &'static [&'static str] = &["disk_size", "needed_space"];
 
-    let lines: Vec<&str> = file_contents.split("\n").map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "disk_size" => self.disk_size = parse_value(key, value, "a size in bytes")?,
+            "needed_space" => self.needed_space = parse_value(key, value, "a size in bytes")?,
+            _ => return Err(ParamError::unknown(key, Self::KEYS)),
+        }
+        Ok(())
+    }
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    type Input = HashMap<Vec<String>, usize>;
+    type Config = Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        calc_directories(contents)
+    }
+
+    fn part1(sizes: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(sizes).into())
+    }
+
+    fn part2(sizes: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(sizes, config)?.into())
+    }
+}
+
+pub fn calc_directories(contents: &str) -> Result<HashMap<Vec<String>, usize>, ParseError> {
+    let lines = get_trimmed_lines(contents);
 
     let mut sizes: HashMap<Vec<&str>, usize> = HashMap::new();
     let mut current_directory: Vec<&str> = Vec::new();
     let mut current_command: Option<&str> = None;
     for line in lines.iter() {
         if line.starts_with("$ ") {
-            let pieces: Vec<&str> = line.split(" ").collect();
+            let pieces: Vec<&str> = line.split(' ').collect();
             current_command = Some(pieces[1]);
             match pieces[1] {
-                "cd" => {
+                "cd" if pieces.len() == 3 => {
                     for (idx, piece) in pieces[2].split("/").enumerate() {
-                        if piece == "" {
+                        if piece.is_empty() {
                             if idx == 0 {
                                 current_directory = Vec::new();
                             }
@@ -34,21 +84,24 @@ fn calc_directories(file_path: &str) -> HashMap<Vec<String>, usize> {
                     // skip
                 },
                 _ => {
-                    panic!("Unknown command {:?}", pieces[1]);
+                    return Err(line.error_at(pieces[1], "a command, either \"cd <dir>\" or \"ls\""));
                 }
             }
 
         }
         else {
             if current_command != Some("ls") {
-                panic!("unknown current command {:?}", current_command);
+                return Err(line.error("a command starting with \"$ \""));
             }
 
-            let pieces: Vec<&str> = line.split(" ").collect();
+            let pieces: Vec<&str> = line.split(' ').collect();
+            if pieces.len() != 2 {
+                return Err(line.error("a listing like \"dir a\" or \"14848514 b.txt\""));
+            }
             if pieces[0] == "dir" {
                 // skip
             } else {
-                let size = pieces[0].parse::<usize>().unwrap();
+                let size = line.parse_at::<usize>(pieces[0], "a file size or \"dir\"")?;
 
                 let mut current_directory_clone = current_directory.clone();
                 while !current_directory_clone.is_empty() {
@@ -61,32 +114,70 @@ fn calc_directories(file_path: &str) -> HashMap<Vec<String>, usize> {
             }
         }
     }
-    sizes.into_iter().map(|(key, value)| (key.iter().map(|path| path.to_string()).collect(), value)).collect()
+    Ok(sizes.into_iter().map(|(key, value)| (key.iter().map(|path| path.to_string()).collect(), value)).collect())
 }
 
-pub fn part1(file_path: &str) -> usize {
-    let sizes = calc_directories(file_path);
-    sizes.iter().filter(|(key, value)| **value <= 100000).map(|(key, value)| value).sum()
+pub fn part1(sizes: &HashMap<Vec<String>, usize>) -> usize {
+    sizes.iter().filter(|(_key, value)| **value <= 100000).map(|(_key, value)| value).sum()
 }
 
 
-pub fn part2(file_path: &str) -> usize {
-    let sizes = calc_directories(file_path);
-    let total_space = 70000000;
+pub fn part2(sizes: &HashMap<Vec<String>, usize>, config: &Config) -> Result<usize, ParseError> {
+    let total_space = config.disk_size;
     let root_key = Vec::new();
-    println!("sizes {:?}", sizes);
-    let used_space = sizes.get(&root_key).unwrap();
-    let free_space = total_space - used_space;
-    println!("free: {:?}", free_space);
-    let sorted_sizes = sizes.iter().sorted_by_key(|(key, value)| *value);
-    println!("sorted: {:?}", sorted_sizes);
-    for (key, value) in sorted_sizes {
+    debug!("sizes {:?}", sizes);
+    // there are no sizes at all when no files are listed
+    let used_space = sizes.get(&root_key).copied().unwrap_or(0);
+    // the files can take up more than a disk_size param
+    let free_space = total_space.saturating_sub(used_space);
+    debug!("free: {:?}", free_space);
+    if free_space >= config.needed_space {
+        return Ok(0);
+    }
+    let sorted_sizes = sizes.iter().sorted_by_key(|(_key, value)| *value);
+    trace!("sorted: {:?}", sorted_sizes);
+    for (_key, value) in sorted_sizes {
         //println!("{:?} {:?}", key, value);
-        if (*value + free_space) >= 30000000 {
-            return *value
+        if (*value + free_space) >= config.needed_space {
+            return Ok(*value)
         }
     }
 
-    panic!("No directory found");
+    Err(ParseError::new(0, 0, "", &format!("a directory which frees up {} bytes when deleted", config.needed_space - free_space)))
 }
 
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "$ cd /\n",
+        "$ ls\n",
+        "dir a\n",
+        "14848514 b.txt\n",
+        "8504156 c.dat\n",
+        "dir d\n",
+        "$ cd a\n",
+        "$ ls\n",
+        "dir e\n",
+        "29116 f\n",
+        "2557 g\n",
+        "62596 h.lst\n",
+        "$ cd e\n",
+        "$ ls\n",
+        "584 i\n",
+        "$ cd ..\n",
+        "$ cd ..\n",
+        "$ cd d\n",
+        "$ ls\n",
+        "4060174 j\n",
+        "8033020 d.log\n",
+        "5626152 d.ext\n",
+        "7214296 k\n",
+    );
+    let sizes = Day7::parse(contents).unwrap();
+    assert_eq!(part1(&sizes), 95437);
+    assert_eq!(part2(&sizes, &Config::default()), Ok(24933642));
+    assert_eq!(part2(&sizes, &Config { disk_size: 50, ..Config::default() }), Ok(48381165));
+    assert_eq!(part2(&Day7::parse("$ cd /\n$ ls\n").unwrap(), &Config::default()), Ok(0));
+    assert_eq!(part2(&sizes, &Config { disk_size: 50, needed_space: 50000000 }).unwrap_err().to_string(), "expected a directory which frees up 50000000 bytes when deleted, found end of input");
+}
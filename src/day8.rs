@@ -1,17 +1,38 @@
-use itertools::{any, iproduct};
+use itertools::any;
+use array2d::Array2D;
 use crate::common::parse_digit_grid;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 
 
-pub fn part1(file_path: &str) -> usize {
-    let array = parse_digit_grid(file_path);
+pub struct Day8;
 
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    type Input = Array2D<u8>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parse_digit_grid(contents)
+    }
+
+    fn part1(array: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(array).into())
+    }
+
+    fn part2(array: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(array).into())
+    }
+}
+
+pub fn part1(array: &Array2D<u8>) -> usize {
     let mut visible_count = 0;
 
     for (row, col) in array.indices_row_major() {
         let n = array[(row, col)];
 
-        let mut is_visible = false;
+        let _is_visible = false;
 
         let iterators: Vec<Vec<(usize, usize)>> = vec![
             (0..row).map(|_r| (_r, col)).collect(),
@@ -41,10 +62,8 @@ pub fn part1(file_path: &str) -> usize {
     visible_count
 }
 
-pub fn part2(file_path: &str) -> usize {
-    let array = parse_digit_grid(file_path);
-
-    let mut visible_count = 0;
+pub fn part2(array: &Array2D<u8>) -> usize {
+    let _visible_count = 0;
 
     let mut best_score = 0;
 
@@ -79,3 +98,17 @@ pub fn part2(file_path: &str) -> usize {
     best_score
 }
 
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "30373\n",
+        "25512\n",
+        "65332\n",
+        "33549\n",
+        "35390\n",
+    );
+    let array = Day8::parse(contents).unwrap();
+    assert_eq!(part1(&array), 21);
+    assert_eq!(part2(&array), 8);
+}
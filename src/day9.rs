@@ -1,41 +1,122 @@
 use std::collections::HashSet;
-use std::fs::create_dir;
-use crate::common::get_trimmed_lines;
+use log::trace;
+use crate::animate::{no_frames, Frame, OnFrame};
+use crate::common::{get_trimmed_lines, Grid};
+use crate::error::ParseError;
+use crate::solution::{Answer, Part, Solution};
 
 
-pub fn part1(file_path: &str) -> usize {
-    let lines = get_trimmed_lines(file_path);
-    let pairs = lines.iter().map(|line| {
-        let pairs: Vec<&str> = line.split(" ").collect();
-        pairs
-    });
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
 
-    let mut head: (i32, i32) = (0, 0);
-    let mut tail: (i32, i32) = (0, 0);
-    let mut tail_history: HashSet<(i32, i32)> = HashSet::new();
-    tail_history.insert((0, 0));
-    for pair in pairs {
-        let direction = pair[0];
+impl Direction {
+    fn letter(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    const ANIMATED: bool = true;
+    type Input = Vec<(Direction, u32)>;
+    type Config = ();
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let lines = get_trimmed_lines(contents);
+        lines.iter().map(|line| {
+            match line.split(' ').collect::<Vec<&str>>()[..] {
+                [direction @ ("U" | "D" | "L" | "R"), count] => {
+                    let direction = match direction {
+                        "U" => Direction::Up,
+                        "D" => Direction::Down,
+                        "L" => Direction::Left,
+                        _ => Direction::Right,
+                    };
+                    Ok((direction, line.parse_at(count, "a step count")?))
+                },
+                [direction, _] => Err(line.error_at(direction, "a direction U, D, L or R")),
+                _ => Err(line.error("a motion like \"R 4\""))
+            }
+        }).collect()
+    }
+
+    fn part1(pairs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part1(pairs).into())
+    }
+
+    fn part2(pairs: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        Ok(part2(pairs).into())
+    }
+
+    fn animate(pairs: &Self::Input, _config: &Self::Config, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(match part {
+            Part::One => move_rope(pairs, on_frame),
+            Part::Two => move_knots(pairs, on_frame),
+        }.into()))
+    }
+}
 
+// The knots drawn over where the tail has been, with the head following rows counting downwards
+fn rope_frame(knots: &[(i64, i64)], tail_history: &HashSet<(i64, i64)>, caption: &str) -> Frame {
+    let mut grid = Grid::new('.');
+    grid.set((0, 0), 's');
+    for visited in tail_history {
+        grid.set((visited.0, visited.1), '#');
+    }
+    for (idx, knot) in knots.iter().enumerate().rev() {
+        let c = match idx {
+            0 => 'H',
+            _ if knots.len() == 2 => 'T',
+            _ => idx.to_string().chars().next().unwrap(),
+        };
+        grid.set((knot.0, knot.1), c);
+    }
+    Frame {
+        grid,
+        focus: Some((knots[0].0, knots[0].1)),
+        caption: caption.to_string(),
+    }
+}
+
+pub fn part1(pairs: &[(Direction, u32)]) -> usize {
+    move_rope(pairs, &mut no_frames)
+}
+
+fn move_rope(pairs: &[(Direction, u32)], on_frame: &mut OnFrame) -> usize {
+    let mut head: (i64, i64) = (0, 0);
+    let mut tail: (i64, i64) = (0, 0);
+    let mut tail_history: HashSet<(i64, i64)> = HashSet::new();
+    tail_history.insert((0, 0));
+    for (direction, count) in pairs {
         let _move = match direction {
-            "U" => {
+            Direction::Up => {
                 (1, 0)
             },
-            "D" => {
+            Direction::Down => {
                 (-1, 0)
             },
-            "R" => {
+            Direction::Right => {
                 (0, 1)
             },
-            "L" => {
+            Direction::Left => {
                 (0, -1)
             },
-            _ => panic!("Unknown direction {}", direction)
         };
+        let caption = format!("{} {}", direction.letter(), count);
 
-        let count = pair[1].parse().unwrap();
-
-        for _ in 0..count {
+        for _ in 0..*count {
             head.0 += _move.0;
             head.1 += _move.1;
 
@@ -58,17 +139,19 @@ pub fn part1(file_path: &str) -> usize {
             }
 
             tail_history.insert(tail);
+            // up is +1 here, so flip it to draw
+            on_frame(&|| rope_frame(&[(-head.0, head.1), (-tail.0, tail.1)], &tail_history.iter().map(|visited| (-visited.0, visited.1)).collect(), &caption));
         }
     }
 
-    println!("tails {:?}", tail_history);
+    trace!("tails {:?}", tail_history);
     tail_history.len()
 }
 
-pub fn print_grid(knots: &Vec<(i32, i32)>) {
+pub fn render_grid(knots: &Vec<(i64, i64)>) -> String {
     let mut bounds = ((-10, -10), (10, 10));
 
-    let mut _expand_bounds = |tup: (i32, i32)| {
+    let mut _expand_bounds = |tup: (i64, i64)| {
         if bounds.0.0 > tup.0 {
             bounds.0.0 = tup.0;
         }
@@ -88,6 +171,7 @@ pub fn print_grid(knots: &Vec<(i32, i32)>) {
         _expand_bounds(*knot);
     }
 
+    let mut grid = String::new();
     for row in bounds.0.0..bounds.1.0 {
         for col in bounds.0.1..bounds.1.1 {
             let mut c =
@@ -109,55 +193,48 @@ pub fn print_grid(knots: &Vec<(i32, i32)>) {
                     }
                 }
             }
-            print!("{}", c);
+            grid.push(c);
         }
-        print!("\n");
+        grid.push('\n');
     }
-    println!();
-    println!();
+    grid
 }
 
-pub fn part2(file_path: &str) -> usize {
-    let lines = get_trimmed_lines(file_path);
-    let pairs = lines.iter().map(|line| {
-        let pairs: Vec<&str> = line.split(" ").collect();
-        pairs
-    });
+pub fn part2(pairs: &[(Direction, u32)]) -> usize {
+    move_knots(pairs, &mut no_frames)
+}
 
-    let mut knots: Vec<(i32, i32)> = Vec::new();
+fn move_knots(pairs: &[(Direction, u32)], on_frame: &mut OnFrame) -> usize {
+    let mut knots: Vec<(i64, i64)> = Vec::new();
     for _ in 0..10 {
         knots.push((0, 0));
     }
-    let mut tail_history: HashSet<(i32, i32)> = HashSet::new();
+    let mut tail_history: HashSet<(i64, i64)> = HashSet::new();
     tail_history.insert((0, 0));
-    for pair in pairs {
-        let direction = pair[0];
-
-        println!("Move {:?}", pair);
+    for (direction, count) in pairs {
+        trace!("Move {:?} {}", direction, count);
         let _move = match direction {
-            "U" => {
+            Direction::Up => {
                 (-1, 0)
             },
-            "D" => {
+            Direction::Down => {
                 (1, 0)
             },
-            "R" => {
+            Direction::Right => {
                 (0, 1)
             },
-            "L" => {
+            Direction::Left => {
                 (0, -1)
             },
-            _ => panic!("Unknown direction {}", direction)
         };
+        let caption = format!("{} {}", direction.letter(), count);
 
-        let count = pair[1].parse().unwrap();
+        for _ in 0..*count {
+            let mut prev_knot_option: Option<(i64, i64)> = None;
+            trace!("move {:?}", _move);
 
-        for _ in 0..count {
-            let mut prev_knot_option: Option<(i32, i32)> = None;
-            println!("move {:?}", _move);
-
-            for mut knot in &mut knots {
-                let knot_move: (i32, i32) = match prev_knot_option {
+            for knot in &mut knots {
+                let knot_move: (i64, i64) = match prev_knot_option {
                     None => _move,
                     Some(prev_knot) => {
                         let diff = (
@@ -165,9 +242,8 @@ pub fn part2(file_path: &str) -> usize {
                             if prev_knot.1 < knot.1 { -1 } else if prev_knot.1 > knot.1 { 1 } else { 0 }
                         );
 
-                        if (knot.0 - prev_knot.0).abs() >= 2 && knot.1 == prev_knot.1 {
-                            diff
-                        } else if (knot.1 - prev_knot.1).abs() >= 2 && knot.0 == prev_knot.0 {
+                        if ((knot.0 - prev_knot.0).abs() >= 2 && knot.1 == prev_knot.1) ||
+                            ((knot.1 - prev_knot.1).abs() >= 2 && knot.0 == prev_knot.0) {
                             diff
                         }
                         else if (knot.0 - prev_knot.0).abs() >= 2 || (knot.1 - prev_knot.1).abs() >= 2 {
@@ -196,14 +272,31 @@ pub fn part2(file_path: &str) -> usize {
                 prev_knot_option = Some(*knot);
             }
 
-            //print_grid(&knots);
+            //trace!("\n{}", render_grid(&knots));
             tail_history.insert(prev_knot_option.unwrap());
+            on_frame(&|| rope_frame(&knots, &tail_history, &caption));
         }
 
-        println!("final");
-        print_grid(&knots);
+        trace!("after {}\n{}", caption, render_grid(&knots));
     }
 
     //println!("tails {:?}", tail_history);
     tail_history.len()
 }
+
+#[test]
+fn test_example() {
+    let contents = concat!(
+        "R 5\n",
+        "U 8\n",
+        "L 8\n",
+        "D 3\n",
+        "R 17\n",
+        "D 10\n",
+        "L 25\n",
+        "U 20\n",
+    );
+    let pairs = Day9::parse(contents).unwrap();
+    assert_eq!(part1(&pairs), 88);
+    assert_eq!(part2(&pairs), 36);
+}
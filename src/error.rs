@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Error for malformed puzzle input. line and column are 1 based, and are 0 when the problem
+// is not tied to a position (for example the file could not be read at all).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, expected: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn io(file_path: &str, err: &io::Error) -> ParseError {
+        ParseError {
+            file: Some(file_path.to_string()),
+            line: 0,
+            column: 0,
+            text: err.to_string(),
+            expected: "a readable input file".to_string(),
+        }
+    }
+
+    pub fn in_file(mut self, file_path: &str) -> ParseError {
+        if self.file.is_none() {
+            self.file = Some(file_path.to_string());
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), 0) => write!(f, "{}: ", file)?,
+            (Some(file), line) => write!(f, "{}:{}:{}: ", file, line, self.column)?,
+            (None, 0) => {},
+            (None, line) => write!(f, "line {}, column {}: ", line, self.column)?,
+        }
+
+        if self.text.is_empty() {
+            write!(f, "expected {}, found end of input", self.expected)
+        } else {
+            write!(f, "expected {}, found {:?}", self.expected, self.text)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[test]
+fn test_display() {
+    let err = ParseError::new(3, 7, "x=abc", "an integer");
+    assert_eq!(err.to_string(), "line 3, column 7: expected an integer, found \"x=abc\"");
+    assert_eq!(err.in_file("day15.txt").to_string(), "day15.txt:3:7: expected an integer, found \"x=abc\"");
+    assert_eq!(ParseError::new(4, 1, "", "a monkey").to_string(), "line 4, column 1: expected a monkey, found end of input");
+}
@@ -0,0 +1,239 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::animate::{Frame, Viewport};
+use crate::common::{Coord, Grid};
+
+pub type Color = [u8; 3];
+
+const UNKNOWN: Color = [255, 255, 255];
+
+// The color each character the simulations draw with is painted, anything else is white
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<(char, Color)>,
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        let mut palette = Palette { colors: Vec::new() };
+        // empty space
+        palette.set('.', [16, 16, 24]);
+        palette.set(' ', [0, 0, 0]);
+        // rock in days 14 and 17, elves in day 23 and the valley walls in day 24
+        palette.set('#', [150, 150, 165]);
+        palette.set('o', [230, 190, 90]);
+        palette.set('+', [255, 120, 0]);
+        palette.set('@', [220, 60, 50]);
+        palette.set('|', [80, 80, 95]);
+        palette.set('-', [80, 80, 95]);
+        for blizzard in ['<', '>', '^', 'v'] {
+            palette.set(blizzard, [120, 180, 255]);
+        }
+        // stacked blizzards and the knots of day 9's rope
+        for digit in '1'..='9' {
+            palette.set(digit, [60, 110, 220]);
+        }
+        palette.set('E', [80, 220, 100]);
+        palette.set('H', [220, 60, 50]);
+        palette.set('T', [230, 190, 90]);
+        palette.set('s', [255, 255, 255]);
+        palette
+    }
+}
+
+impl Palette {
+    pub fn set(&mut self, c: char, color: Color) {
+        match self.colors.iter_mut().find(|(other, _color)| *other == c) {
+            Some(entry) => entry.1 = color,
+            None => self.colors.push((c, color)),
+        }
+    }
+
+    pub fn color(&self, c: char) -> Color {
+        self.colors.iter().find(|(other, _color)| *other == c).map(|(_c, color)| *color).unwrap_or(UNKNOWN)
+    }
+
+    // Where c is in the GIF palette, which has the unknown color after all the others
+    fn index(&self, c: char) -> u8 {
+        self.colors.iter().position(|(other, _color)| *other == c).unwrap_or(self.colors.len()) as u8
+    }
+
+    fn gif_palette(&self) -> Vec<u8> {
+        self.colors.iter().map(|(_c, color)| *color).chain([UNKNOWN]).flatten().collect()
+    }
+}
+
+// Used as a clap value parser for --color, like #=ff0000
+pub fn parse_color(color: &str) -> Result<(char, Color), String> {
+    let err = || format!("expected a character and a hex color like #=ff8800, found {:?}", color);
+    // == is = itself being given a color
+    let (c, hex) = match color.strip_prefix("==") {
+        Some(hex) => ("=", hex),
+        None => color.split_once('=').ok_or_else(err)?,
+    };
+    let mut chars = c.chars();
+    let c = match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => return Err(err()),
+    };
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(err());
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| err());
+    Ok((c, [channel(0)?, channel(2)?, channel(4)?]))
+}
+
+// Keeps every nth frame of a simulation, cropped to the viewport if there is one
+pub struct Recorder {
+    every: u64,
+    steps: u64,
+    viewport: Option<Viewport>,
+    pub frames: Vec<Grid<char>>,
+}
+
+impl Recorder {
+    pub fn new(every: u64, viewport: Option<Viewport>) -> Recorder {
+        Recorder { every: every.max(1), steps: 0, viewport, frames: Vec::new() }
+    }
+
+    pub fn record(&mut self, frame: &dyn Fn() -> Frame) {
+        self.steps += 1;
+        if !(self.steps - 1).is_multiple_of(self.every) {
+            return;
+        }
+
+        let frame = frame();
+        let grid = match self.viewport.as_mut() {
+            Some(viewport) => {
+                let rows: Vec<Vec<char>> = viewport.crop(&frame).iter().map(|line| line.chars().collect()).collect();
+                Grid::from_rows(&rows, *frame.grid.fill())
+            },
+            None => frame.grid,
+        };
+        self.frames.push(grid);
+    }
+}
+
+// Frames can grow as the simulation goes, like day 23's elves spreading out, so the picture covers all of them
+fn canvas(frames: &[Grid<char>]) -> (Coord, Coord) {
+    let mins = (frames.iter().map(|grid| grid.mins().0).min().unwrap_or(0), frames.iter().map(|grid| grid.mins().1).min().unwrap_or(0));
+    let maxes = (frames.iter().map(|grid| grid.maxes().0).max().unwrap_or(0), frames.iter().map(|grid| grid.maxes().1).max().unwrap_or(0));
+    (mins, maxes)
+}
+
+// Width and height in pixels
+fn canvas_size(bounds: (Coord, Coord), scale: usize) -> (usize, usize) {
+    let (mins, maxes) = bounds;
+    (((maxes.1 - mins.1 + 1).max(0) as usize) * scale, ((maxes.0 - mins.0 + 1).max(0) as usize) * scale)
+}
+
+// Each cell becomes a scale by scale square of whatever pixel returns for it, row by row
+fn paint<P: Copy>(grid: &Grid<char>, bounds: (Coord, Coord), scale: usize, pixel: impl Fn(char) -> P) -> Vec<P> {
+    let (mins, maxes) = bounds;
+    let mut pixels = Vec::new();
+    for row in mins.0..=maxes.0 {
+        let line: Vec<P> = (mins.1..=maxes.1).flat_map(|col| {
+            let p = pixel(*grid.get_or_fill((row, col)));
+            (0..scale).map(move |_| p)
+        }).collect();
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+    pixels
+}
+
+pub fn write_gif(out: impl Write, frames: &[Grid<char>], palette: &Palette, scale: usize, fps: f64) -> Result<(), String> {
+    let bounds = canvas(frames);
+    let (width, height) = canvas_size(bounds, scale);
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => (width, height),
+        _ => return Err(format!("{}x{} pixels is too big for a GIF, try --viewport or a smaller --scale", width, height)),
+    };
+    if palette.colors.len() >= 256 {
+        return Err("a GIF can only have 256 colors".to_string());
+    }
+
+    // GIF delays are in hundredths of a second and most viewers ignore anything under 2
+    let delay = ((100.0 / fps).round() as u16).max(2);
+    let mut encoder = gif::Encoder::new(out, width, height, &palette.gif_palette()).map_err(|err| err.to_string())?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(|err| err.to_string())?;
+    for grid in frames {
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, paint(grid, bounds, scale, |c| palette.index(c)), None);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn write_png(out: impl Write, grid: &Grid<char>, bounds: (Coord, Coord), palette: &Palette, scale: usize) -> Result<(), String> {
+    let (width, height) = canvas_size(bounds, scale);
+    let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+    let pixels: Vec<u8> = paint(grid, bounds, scale, |c| palette.color(c)).into_iter().flatten().collect();
+    writer.write_image_data(&pixels).map_err(|err| err.to_string())?;
+    writer.finish().map_err(|err| err.to_string())
+}
+
+// out.png becomes out-00001.png, out-00002.png and so on
+pub fn png_path(path: &Path, idx: usize) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!("{}-{:05}.png", stem, idx + 1))
+}
+
+// A .gif path gets one animated GIF, anything else a numbered PNG per frame. Returns the files written.
+pub fn write_frames(path: &Path, frames: &[Grid<char>], palette: &Palette, scale: usize, fps: f64) -> Result<Vec<PathBuf>, String> {
+    let create = |path: &Path| File::create(path).map(BufWriter::new).map_err(|err| format!("{}: {}", path.display(), err));
+    if frames.is_empty() {
+        return Err("the simulation didn't draw any frames".to_string());
+    }
+
+    if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif")) {
+        write_gif(create(path)?, frames, palette, scale, fps).map_err(|err| format!("{}: {}", path.display(), err))?;
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let bounds = canvas(frames);
+    frames.iter().enumerate().map(|(idx, grid)| {
+        let path = png_path(path, idx);
+        write_png(create(&path)?, grid, bounds, palette, scale).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(path)
+    }).collect()
+}
+
+#[test]
+fn test_export() {
+    assert_eq!(parse_color("#=ff8800"), Ok(('#', [255, 136, 0])));
+    assert_eq!(parse_color("o=#0a0B0c"), Ok(('o', [10, 11, 12])));
+    assert_eq!(parse_color("==000000"), Ok(('=', [0, 0, 0])));
+    assert!(parse_color("#=ff88").is_err());
+    assert!(parse_color("ab=ff8800").is_err());
+
+    let mut palette = Palette::default();
+    palette.set('#', [1, 2, 3]);
+    assert_eq!(palette.color('#'), [1, 2, 3]);
+    assert_eq!(palette.color('?'), UNKNOWN);
+
+    let grid = Grid::from_rows(&[vec!['#', '.']], '.');
+    let mut grown = grid.clone();
+    grown.set((1, 0), '?');
+    let frames = vec![grid, grown];
+    assert_eq!(canvas(&frames), ((0, 0), (1, 1)));
+    let indices = paint(&frames[0], canvas(&frames), 2, |c| palette.index(c));
+    assert_eq!(indices.len(), 16);
+    assert_eq!(&indices[..4], &[palette.index('#'), palette.index('#'), palette.index('.'), palette.index('.')]);
+
+    let mut gif = Vec::new();
+    write_gif(&mut gif, &frames, &palette, 2, 10.0).unwrap();
+    assert!(gif.starts_with(b"GIF89a"));
+
+    let mut png = Vec::new();
+    write_png(&mut png, &frames[1], canvas(&frames), &palette, 3).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    assert_eq!(png_path(Path::new("out/sand.png"), 0), Path::new("out/sand-00001.png"));
+}
@@ -1,172 +1,453 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod common;
-
-use clap::{ Arg, App };
+use std::cmp::max;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+use clap::{ Arg, App, ArgAction, ArgMatches };
+use itertools::Itertools;
+use log::LevelFilter;
+use code_advent_2022::animate::{self, Player, Viewport};
+use code_advent_2022::bench::{self, Budget};
+use code_advent_2022::common::input_name;
+use code_advent_2022::error::ParseError;
+use code_advent_2022::export::{self, Palette, Recorder};
+use code_advent_2022::params::{self, Params};
+use code_advent_2022::runner::{self, format_duration};
+use code_advent_2022::solution::{self, Parsed, Part, Puzzle};
+use code_advent_2022::verify::{self, Manifest, Verdict};
+
+fn get_puzzle(matches: &ArgMatches) -> (&'static dyn Puzzle, Part) {
+    let day: u8 = *matches.get_one("day").unwrap();
+    let part: u16 = *matches.get_one("part").unwrap();
+
+    let puzzle = solution::get(day).unwrap_or_else(|| panic!("Unknown day {}", day));
+    let part = Part::from_number(part).unwrap_or_else(|| panic!("Unknown part {}", part));
+    (puzzle, part)
+}
+
+// Parses the input and applies any --param, exiting on errors
+fn load_input(puzzle: &'static dyn Puzzle, matches: &ArgMatches) -> Parsed<'static> {
+    let file_path: &String = matches.get_one("input").unwrap();
+    let mut params: Params = matches.get_many::<(String, String)>("param").map(|params| params.cloned().collect()).unwrap_or_default();
+    // shorthands for the params of the same name, which only running a day has
+    for key in ["agents", "minutes"] {
+        if let Ok(Some(value)) = matches.try_get_one::<u64>(key) {
+            params.set(key, &value.to_string());
+        }
+    }
+
+    let mut input = match puzzle.parse_file(file_path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    if let Err(err) = input.configure(&params) {
+        eprintln!("error: day {}: {}", puzzle.day(), err);
+        process::exit(1);
+    }
+    input
+}
+
+fn run_one(matches: &ArgMatches) {
+    let (puzzle, part) = get_puzzle(matches);
+    let day = puzzle.day();
+
+    let quiet = matches.contains_id("quiet");
+    if !quiet {
+        println!("Day {}, part {}: ", day, part.number());
+    }
+    let input = load_input(puzzle, matches);
+    match input.solve(part) {
+        Some(Err(err)) => part_failed(err, matches),
+        Some(Ok(result)) if quiet => println!("{}", result),
+        Some(Ok(result)) => {
+            if let Some(explanation) = result.explanation() {
+                print!("{}", explanation);
+            }
+            println!("Result: {}", result)
+        },
+        None => println!("Day {} has no part {}", day, part.number()),
+    }
+}
+
+// Inputs which a part can't solve are reported like inputs which don't parse
+fn part_failed(err: ParseError, matches: &ArgMatches) -> ! {
+    eprintln!("error: {}", err.in_file(input_name(matches.get_one::<String>("input").unwrap())));
+    process::exit(1);
+}
+
+fn run_all(matches: &ArgMatches) {
+    let inputs_dir: &String = matches.get_one("inputs").unwrap();
+    let jobs = *matches.get_one::<u64>("jobs").unwrap() as usize;
+    let start = Instant::now();
+    let runs = runner::run_all(Path::new(inputs_dir), jobs);
+    let wall_time = start.elapsed();
+
+    println!("{:>3}  {:>4}  {:<20}  {:>9}", "Day", "Part", "Answer", "Time");
+    let mut solved = 0;
+    let mut failed = 0;
+    let mut skipped = 0;
+    let mut parse_time = Duration::ZERO;
+    let mut solve_time = Duration::ZERO;
+    for (day, run) in runs.iter() {
+        let run = match run {
+            Some(run) => run,
+            None => {
+                println!("{:>3}  {:>4}  skipped, no {}", day, "", runner::input_path(Path::new(inputs_dir), *day).display());
+                skipped += 1;
+                continue;
+            }
+        };
+
+        parse_time += run.parse_time;
+        solve_time += run.solve_time();
+        failed += run.failures();
+        match &run.parts {
+            Err(err) => println!("{:>3}  {:>4}  FAILED {}", day, "", err),
+            Ok(parts) => {
+                for part in parts {
+                    match &part.answer {
+                        Ok(answer) => {
+                            solved += 1;
+                            // multi line answers like day 10's picture go underneath the row
+                            let answer = answer.to_string();
+                            let multi_line = answer.trim().contains('\n');
+                            println!("{:>3}  {:>4}  {:<20}  {:>9}", day, part.part.number(), if multi_line { "" } else { &answer }, format_duration(part.time));
+                            if multi_line {
+                                for line in answer.trim().lines() {
+                                    println!("{:>3}  {:>4}  {}", "", "", line);
+                                }
+                            }
+                        },
+                        Err(err) => println!("{:>3}  {:>4}  FAILED {}", day, part.part.number(), err),
+                    }
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} parts solved, {} failed, {} days skipped", solved, failed, skipped);
+    println!("parse {}, solve {}, total {}", format_duration(parse_time), format_duration(solve_time), format_duration(parse_time + solve_time));
+    if jobs > 1 {
+        // days overlap, so this is less than the total
+        println!("{} on {} threads", format_duration(wall_time), jobs);
+    }
+
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+fn run_bench(matches: &ArgMatches) {
+    let inputs_dir = Path::new(matches.get_one::<String>("inputs").unwrap());
+    let days: Vec<u8> = match matches.get_many::<u8>("day") {
+        Some(days) => {
+            let days: Vec<u8> = days.copied().collect();
+            for day in days.iter() {
+                let path = runner::input_path(inputs_dir, *day);
+                if !path.is_file() {
+                    eprintln!("warning: skipping day {}, no {}", day, path.display());
+                }
+            }
+            days
+        },
+        None => solution::SOLUTIONS.iter().map(|puzzle| puzzle.day()).collect(),
+    };
+    let budget = Budget {
+        samples: *matches.get_one::<usize>("samples").unwrap(),
+        max_time: Duration::from_secs_f64(*matches.get_one::<f64>("max-time").unwrap()),
+    };
+    let threshold: f64 = *matches.get_one("threshold").unwrap();
+    let previous = matches.get_one::<String>("compare").map(|path| match bench::load_results(Path::new(path)) {
+        Ok(previous) => previous,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    });
+
+    println!("{}", bench::header(previous.is_some()));
+    let mut failed = 0;
+    let results = bench::bench_days(inputs_dir, &days, &budget, |day, steps| {
+        println!("{}", bench::format_day(day, steps, previous.as_ref(), threshold));
+        if steps.is_err() {
+            failed += 1;
+        }
+    });
+    if results.is_empty() && failed == 0 {
+        println!("no inputs found in {}", inputs_dir.display());
+    }
+
+    if let Some(output) = matches.get_one::<String>("output") {
+        if let Err(err) = fs::write(output, bench::results_to_json(&results) + "\n") {
+            eprintln!("error: {}: {}", output, err);
+            process::exit(1);
+        }
+    }
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+fn animate(matches: &ArgMatches) {
+    let (puzzle, part) = get_puzzle(matches);
+    if !puzzle.animated() {
+        let days: Vec<String> = solution::SOLUTIONS.iter().filter(|puzzle| puzzle.animated()).map(|puzzle| puzzle.day().to_string()).collect();
+        eprintln!("error: day {} has no animation, try day {}", puzzle.day(), days.join(", "));
+        process::exit(1);
+    }
+    let input = load_input(puzzle, matches);
+
+    let fps: f64 = *matches.get_one("fps").unwrap();
+    let viewport = matches.get_one::<(usize, usize)>("viewport").copied();
+    let answer = {
+        // dropping the player puts the terminal back before the answer is printed
+        let mut player = Player::new(fps, viewport, matches.contains_id("follow"));
+        input.animate(part, &mut |frame| player.show(frame))
+    };
+    match answer {
+        Some(Ok(answer)) => println!("Result: {}", answer),
+        Some(Err(err)) => part_failed(err, matches),
+        None => println!("Day {} has no part {}", puzzle.day(), part.number()),
+    }
+}
+
+fn export(matches: &ArgMatches) {
+    let (puzzle, part) = get_puzzle(matches);
+    if !puzzle.animated() {
+        let days: Vec<String> = solution::SOLUTIONS.iter().filter(|puzzle| puzzle.animated()).map(|puzzle| puzzle.day().to_string()).collect();
+        eprintln!("error: day {} has no frames to export, try day {}", puzzle.day(), days.join(", "));
+        process::exit(1);
+    }
+    let input = load_input(puzzle, matches);
+
+    let output = Path::new(matches.get_one::<String>("output").unwrap());
+    let scale: usize = *matches.get_one::<u16>("scale").unwrap() as usize;
+    let fps: f64 = *matches.get_one("fps").unwrap();
+    let every: u64 = *matches.get_one("every").unwrap();
+    let mut palette = Palette::default();
+    for (c, color) in matches.get_many::<(char, export::Color)>("color").into_iter().flatten() {
+        palette.set(*c, *color);
+    }
+    let viewport = matches.get_one::<(usize, usize)>("viewport").map(|(rows, columns)| Viewport::new(*rows, *columns, matches.contains_id("follow")));
+
+    let mut recorder = Recorder::new(every, viewport);
+    let answer = input.animate(part, &mut |frame| recorder.record(frame));
+    match answer {
+        Some(Ok(answer)) => println!("Result: {}", answer),
+        Some(Err(err)) => part_failed(err, matches),
+        None => {
+            println!("Day {} has no part {}", puzzle.day(), part.number());
+            return;
+        }
+    }
+
+    match export::write_frames(output, &recorder.frames, &palette, scale, fps) {
+        Ok(paths) if paths.len() == 1 => println!("Wrote {} frames to {}", recorder.frames.len(), paths[0].display()),
+        Ok(paths) => println!("Wrote {} frames to {} to {}", paths.len(), paths[0].display(), paths[paths.len() - 1].display()),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_fps(fps: &str) -> Result<f64, String> {
+    match fps.parse::<f64>() {
+        Ok(fps) if fps > 0.0 && fps.is_finite() => Ok(fps),
+        _ => Err(format!("expected a positive number of frames per second, found {:?}", fps)),
+    }
+}
+
+fn parse_seconds(secs: &str) -> Result<f64, String> {
+    match secs.parse::<f64>() {
+        Ok(secs) if secs >= 0.0 && secs.is_finite() => Ok(secs),
+        _ => Err(format!("expected a number of seconds, found {:?}", secs)),
+    }
+}
+
+fn print_diff(expected: &str, actual: &str) {
+    let expected: Vec<&str> = expected.trim().lines().collect();
+    let actual: Vec<&str> = actual.trim().lines().collect();
+    for idx in 0..max(expected.len(), actual.len()) {
+        match (expected.get(idx), actual.get(idx)) {
+            (Some(expected), Some(actual)) if expected == actual => println!("      {}", expected),
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    println!("    - {}", expected);
+                }
+                if let Some(actual) = actual {
+                    println!("    + {}", actual);
+                }
+            }
+        }
+    }
+}
+
+fn verify(matches: &ArgMatches) {
+    let inputs_dir = Path::new(matches.get_one::<String>("inputs").unwrap());
+    let answers_path = match matches.get_one::<String>("answers") {
+        Some(answers) => PathBuf::from(answers),
+        None => inputs_dir.join("answers.json"),
+    };
+    let record = matches.contains_id("record");
+
+    let mut manifest = match verify::load_manifest(&answers_path) {
+        Ok(manifest) => manifest,
+        Err(_) if record && !answers_path.exists() => Manifest::new(),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let days: Vec<u8> = match matches.get_many::<u8>("day") {
+        Some(days) => days.copied().collect(),
+        None if record => solution::SOLUTIONS.iter().map(|puzzle| puzzle.day()).collect(),
+        None => manifest.keys().copied().collect(),
+    };
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for (day, run) in runner::run_days(inputs_dir, &days, 1) {
+        let run = match run {
+            Some(run) => run,
+            None => {
+                let path = runner::input_path(inputs_dir, day);
+                if manifest.contains_key(&day) {
+                    println!("Day {:>2}: ERROR no input {}", day, path.display());
+                    *counts.entry("errors").or_default() += 1;
+                }
+                continue;
+            }
+        };
+
+        for (part, verdict) in verify::verify_day(&run, &manifest) {
+            let label = format!("Day {:>2} part {}", day, part.number());
+            match verdict {
+                Verdict::Pass => {
+                    println!("{}: PASS", label);
+                    *counts.entry("passed").or_default() += 1;
+                },
+                Verdict::Fail { expected, actual } => {
+                    println!("{}: FAIL", label);
+                    print_diff(&expected, &actual);
+                    *counts.entry("failed").or_default() += 1;
+                },
+                Verdict::Error(err) => {
+                    println!("{}: ERROR {}", label, err);
+                    *counts.entry("errors").or_default() += 1;
+                },
+                Verdict::Unknown(actual) => {
+                    if record {
+                        println!("{}: RECORDED {}", label, actual.trim());
+                        manifest.entry(day).or_default().insert(part.number(), actual.trim().to_string());
+                        *counts.entry("recorded").or_default() += 1;
+                    } else {
+                        println!("{}: no expected answer, got {}", label, actual.trim());
+                        *counts.entry("unchecked").or_default() += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    if record {
+        if let Err(err) = fs::write(&answers_path, verify::manifest_to_json(&manifest) + "\n") {
+            eprintln!("error: {}: {}", answers_path.display(), err);
+            process::exit(1);
+        }
+    }
+
+    println!();
+    println!("{}", counts.iter().map(|(name, count)| format!("{} {}", count, name)).join(", "));
+
+    if counts.contains_key("failed") || counts.contains_key("errors") {
+        process::exit(1);
+    }
+}
+
+fn init_logging(matches: &ArgMatches) {
+    // solvers log their working at debug and trace, so a normal run only prints answers
+    let level = match (matches.contains_id("quiet"), matches.get_count("verbose")) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Warn,
+        (false, 1) => LevelFilter::Debug,
+        (false, _) => LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
+}
 
 fn main() {
     let matches = App::new("Code of Advent 2022")
-        .arg(Arg::with_name("day").takes_value(true).required(true).value_parser(clap::value_parser!(u16).range(1..=25)))
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+        .arg(Arg::with_name("verbose").short('v').long("verbose").action(ArgAction::Count).global(true).help("Log what the solvers are doing, -vv for every step"))
+        .arg(Arg::with_name("quiet").short('q').long("quiet").global(true).conflicts_with("verbose").help("Only print answers and errors"))
+        .arg(Arg::with_name("day").takes_value(true).required(true).value_parser(clap::value_parser!(u8).range(1..=25)))
         .arg(Arg::with_name("part").takes_value(true).required(true).value_parser(clap::value_parser!(u16).range(1..=2)))
-        .arg(Arg::with_name("input").takes_value(true).required(true))
+        .arg(Arg::with_name("input").takes_value(true).required(true).help("Input file, or - to read from stdin"))
+        .arg(Arg::with_name("param").long("param").takes_value(true).multiple_occurrences(true).value_parser(params::parse_param).help("Puzzle setting as key=value, for example row=10 for day 15's example"))
+        .arg(Arg::with_name("agents").long("agents").takes_value(true).value_parser(clap::value_parser!(u64).range(1..)).help("Same as --param agents=N, how many open valves at once in day 16"))
+        .arg(Arg::with_name("minutes").long("minutes").takes_value(true).value_parser(clap::value_parser!(u64).range(1..)).help("Same as --param minutes=N, the time day 16's valves can be opened in, less the training minutes in part 2"))
+        .subcommand(App::new("animate")
+            .about("Plays a simulation step by step in the terminal. Keys: space pause, n step, +/- speed, f follow, q quit")
+            .arg(Arg::with_name("day").takes_value(true).required(true).value_parser(clap::value_parser!(u8).range(1..=25)))
+            .arg(Arg::with_name("part").takes_value(true).required(true).value_parser(clap::value_parser!(u16).range(1..=2)))
+            .arg(Arg::with_name("input").takes_value(true).required(true).help("Input file, or - to read from stdin"))
+            .arg(Arg::with_name("param").long("param").takes_value(true).multiple_occurrences(true).value_parser(params::parse_param).help("Puzzle setting as key=value"))
+            .arg(Arg::with_name("fps").long("fps").takes_value(true).default_value("10").value_parser(parse_fps).help("Simulation steps per second"))
+            .arg(Arg::with_name("viewport").long("viewport").takes_value(true).value_parser(animate::parse_viewport).help("Size of the window onto the simulation as ROWSxCOLUMNS, defaults to the terminal size"))
+            .arg(Arg::with_name("follow").long("follow").help("Scroll to keep the moving part of the simulation in view")))
+        .subcommand(App::new("export")
+            .about("Saves the frames of a simulation as an animated GIF, or as numbered PNGs")
+            .arg(Arg::with_name("day").takes_value(true).required(true).value_parser(clap::value_parser!(u8).range(1..=25)))
+            .arg(Arg::with_name("part").takes_value(true).required(true).value_parser(clap::value_parser!(u16).range(1..=2)))
+            .arg(Arg::with_name("input").takes_value(true).required(true).help("Input file, or - to read from stdin"))
+            .arg(Arg::with_name("param").long("param").takes_value(true).multiple_occurrences(true).value_parser(params::parse_param).help("Puzzle setting as key=value"))
+            .arg(Arg::with_name("output").short('o').long("output").takes_value(true).required(true).help("out.gif for an animated GIF, or out.png for out-00001.png, out-00002.png and so on"))
+            .arg(Arg::with_name("scale").long("scale").takes_value(true).default_value("4").value_parser(clap::value_parser!(u16).range(1..=64)).help("Pixels along each side of a cell"))
+            .arg(Arg::with_name("fps").long("fps").takes_value(true).default_value("10").value_parser(parse_fps).help("Frames per second of the GIF"))
+            .arg(Arg::with_name("every").long("every").takes_value(true).default_value("1").value_parser(clap::value_parser!(u64).range(1..)).help("Only keep every nth step of the simulation"))
+            .arg(Arg::with_name("color").long("color").takes_value(true).multiple_occurrences(true).value_parser(export::parse_color).help("Color for a character as c=rrggbb, for example o=ffcc00"))
+            .arg(Arg::with_name("viewport").long("viewport").takes_value(true).value_parser(animate::parse_viewport).help("Crop every frame to ROWSxCOLUMNS, defaults to the whole simulation"))
+            .arg(Arg::with_name("follow").long("follow").requires("viewport").help("Scroll the viewport to keep the moving part of the simulation in view")))
+        .subcommand(App::new("all")
+            .about("Runs every day which has an input file and prints a table of answers")
+            .arg(Arg::with_name("inputs").long("inputs").takes_value(true).default_value("inputs").help("Directory holding day01.txt to day25.txt"))
+            .arg(Arg::with_name("jobs").short('j').long("jobs").takes_value(true).default_value("1").value_parser(clap::value_parser!(u64).range(1..)).help("Run this many days at once, the table still comes out in order")))
+        .subcommand(App::new("bench")
+            .about("Times parsing and solving every day which has an input file")
+            .arg(Arg::with_name("inputs").long("inputs").takes_value(true).default_value("inputs").help("Directory holding day01.txt to day25.txt"))
+            .arg(Arg::with_name("day").long("day").takes_value(true).multiple_occurrences(true).value_parser(clap::value_parser!(u8).range(1..=25)).help("Only bench this day"))
+            .arg(Arg::with_name("samples").long("samples").takes_value(true).default_value("10").value_parser(clap::value_parser!(usize)).help("Times to run each step"))
+            .arg(Arg::with_name("max-time").long("max-time").takes_value(true).default_value("5").value_parser(parse_seconds).help("Stop sampling a step after this many seconds, though every step runs at least once"))
+            .arg(Arg::with_name("output").long("output").takes_value(true).help("Save the timings as JSON"))
+            .arg(Arg::with_name("compare").long("compare").takes_value(true).help("Timings saved by an earlier --output to compare against"))
+            .arg(Arg::with_name("threshold").long("threshold").takes_value(true).default_value("5").value_parser(clap::value_parser!(f64)).help("Percent change in the mean below which a step counts as unchanged")))
+        .subcommand(App::new("verify")
+            .about("Checks answers against a manifest of expected answers, failing on any regression")
+            .arg(Arg::with_name("inputs").long("inputs").takes_value(true).default_value("inputs").help("Directory holding day01.txt to day25.txt"))
+            .arg(Arg::with_name("answers").long("answers").takes_value(true).help("Expected answers as JSON, defaults to answers.json in the inputs directory"))
+            .arg(Arg::with_name("day").long("day").takes_value(true).multiple_occurrences(true).value_parser(clap::value_parser!(u8).range(1..=25)).help("Only verify this day"))
+            .arg(Arg::with_name("record").long("record").help("Add answers for parts which have no expected answer yet")))
         .get_matches();
 
-    let file_path: &String = matches.get_one("input").unwrap();
-    let day: u16 = *matches.get_one("day").unwrap();
-    let part: u16 = *matches.get_one("part").unwrap();
-
-    println!("Day {}, part {}: ", day, part);
-    let result: String =
-        match day {
-            1 => match part {
-                1 => day1::part1(file_path).to_string(),
-                2 => day1::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            2 => match part {
-                1 => day2::part1(file_path).to_string(),
-                2 => day2::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            3 => match part {
-                1 => day3::part1(file_path).to_string(),
-                2 => day3::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            4 => match part {
-                1 => day4::part1(file_path).to_string(),
-                2 => day4::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            5 => match part {
-                1 => day5::part1(file_path).to_string(),
-                2 => day5::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            6 => match part {
-                1 => day6::part1(file_path).to_string(),
-                2 => day6::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            7 => match part {
-                1 => day7::part1(file_path).to_string(),
-                2 => day7::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            8 => match part {
-                1 => day8::part1(file_path).to_string(),
-                2 => day8::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            9 => match part {
-                1 => day9::part1(file_path).to_string(),
-                2 => day9::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            10 => match part {
-                1 => day10::part1(file_path).to_string(),
-                2 => day10::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            11 => match part {
-                1 => day11::part1(file_path).to_string(),
-                2 => day11::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            12 => match part {
-                1 => day12::part1(file_path).to_string(),
-                2 => day12::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            13 => match part {
-                1 => day13::part1(file_path).to_string(),
-                2 => day13::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            14 => match part {
-                1 => day14::part1(file_path).to_string(),
-                2 => day14::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            15 => match part {
-                1 => day15::part1(file_path).to_string(),
-                2 => day15::part2(file_path).to_string(),
-                _ => panic!("Unknown part {}", part)
-            },
-            16 => match part {
-                1 => day16::part1(file_path).to_string(),
-                2 => day16::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            17 => match part {
-                1 => day17::part1(file_path).to_string(),
-                2 => day17::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            18 => match part {
-                1 => day18::part1(file_path).to_string(),
-                2 => day18::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            19 => match part {
-                1 => day19::part1(file_path).to_string(),
-                2 => day19::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            20 => match part {
-                1 => day20::part1(file_path).to_string(),
-                2 => day20::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            21 => match part {
-                1 => day21::part1(file_path).to_string(),
-                2 => day21::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            22 => match part {
-                1 => day22::part1(file_path).to_string(),
-                2 => day22::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            23 => match part {
-                1 => day23::part1(file_path).to_string(),
-                2 => day23::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            24 => match part {
-                1 => day24::part1(file_path).to_string(),
-                2 => day24::part2(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            25 => match part {
-                1 => day25::part1(file_path).to_string(),
-                _ => panic!("Unknown {}", part)
-            },
-            _ => panic!("Unknown day {}", day)
-        };
+    init_logging(&matches);
 
-    println!("Result: {}", result);
+    match matches.subcommand() {
+        Some(("animate", sub_matches)) => animate(sub_matches),
+        Some(("export", sub_matches)) => export(sub_matches),
+        Some(("all", sub_matches)) => run_all(sub_matches),
+        Some(("bench", sub_matches)) => run_bench(sub_matches),
+        Some(("verify", sub_matches)) => verify(sub_matches),
+        _ => run_one(&matches),
+    }
 }
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::error::Error;
+use std::str::FromStr;
+
+// key=value pairs given with --param, in the order they were given
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    pub fn new() -> Params {
+        Params(Vec::new())
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.0.push((key.to_string(), value.to_string()));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+impl FromIterator<(String, String)> for Params {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Params {
+        Params(iter.into_iter().collect())
+    }
+}
+
+// Used as a clap value parser for --param
+pub fn parse_param(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("expected key=value, found {:?}", param)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    Unknown { key: String, known: &'static [&'static str] },
+    Invalid { key: String, value: String, expected: &'static str },
+}
+
+impl ParamError {
+    pub fn unknown(key: &str, known: &'static [&'static str]) -> ParamError {
+        ParamError::Unknown { key: key.to_string(), known }
+    }
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Unknown { key, known: [] } => write!(f, "unknown parameter {:?}, this day takes no parameters", key),
+            ParamError::Unknown { key, known } => write!(f, "unknown parameter {:?}, expected one of {}", key, known.join(", ")),
+            ParamError::Invalid { key, value, expected } => write!(f, "invalid value {:?} for {}, expected {}", value, key, expected),
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+pub fn parse_value<T: FromStr>(key: &str, value: &str, expected: &'static str) -> Result<T, ParamError> {
+    value.parse().map_err(|_| ParamError::Invalid { key: key.to_string(), value: value.to_string(), expected })
+}
+
+// The typed settings for a day, like which row day 15 looks at. Default is what the real puzzle uses.
+pub trait Config: Default + 'static {
+    const KEYS: &'static [&'static str];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError>;
+
+    fn from_params(params: &Params) -> Result<Self, ParamError> {
+        let mut config = Self::default();
+        for (key, value) in params.iter() {
+            config.set(key, value)?;
+        }
+        Ok(config)
+    }
+}
+
+impl Config for () {
+    const KEYS: &'static [&'static str] = &[];
+
+    fn set(&mut self, key: &str, _value: &str) -> Result<(), ParamError> {
+        Err(ParamError::unknown(key, Self::KEYS))
+    }
+}
+
+#[test]
+fn test_params() {
+    assert_eq!(parse_param("row=10"), Ok(("row".to_string(), "10".to_string())));
+    assert!(parse_param("row").is_err());
+    assert!(parse_param("=10").is_err());
+
+    let params: Params = [("row".to_string(), "x".to_string())].into_iter().collect();
+    assert_eq!(<()>::from_params(&Params::new()), Ok(()));
+    assert_eq!(<()>::from_params(&params).unwrap_err().to_string(), "unknown parameter \"row\", this day takes no parameters");
+    assert_eq!(parse_value::<i64>("row", "x", "an integer").unwrap_err().to_string(), "invalid value \"x\" for row, expected an integer");
+}
@@ -0,0 +1,128 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::common::parallel_map;
+use crate::solution::{Answer, Part, Puzzle, SOLUTIONS};
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<Answer, String>,
+    pub time: Duration,
+}
+
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub input: PathBuf,
+    pub parse_time: Duration,
+    // Err if the input could not be parsed, in which case no parts were run
+    pub parts: Result<Vec<PartRun>, String>,
+}
+
+impl DayRun {
+    pub fn failures(&self) -> usize {
+        match &self.parts {
+            Ok(parts) => parts.iter().filter(|part| part.answer.is_err()).count(),
+            Err(_) => 1,
+        }
+    }
+
+    pub fn solve_time(&self) -> Duration {
+        match &self.parts {
+            Ok(parts) => parts.iter().map(|part| part.time).sum(),
+            Err(_) => Duration::ZERO,
+        }
+    }
+}
+
+pub fn input_path(inputs_dir: &Path, day: u8) -> PathBuf {
+    inputs_dir.join(format!("day{:02}.txt", day))
+}
+
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => format!("panicked: {}", message),
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => format!("panicked: {}", message),
+            Err(_) => "panicked".to_string(),
+        }
+    }
+}
+
+// Runs every part of a day, catching panics so one broken solver doesn't stop the others
+pub fn run_day(puzzle: &dyn Puzzle, input: &Path) -> DayRun {
+    let start = Instant::now();
+    let parsed = panic::catch_unwind(AssertUnwindSafe(|| puzzle.parse_file(&input.to_string_lossy())));
+    let parse_time = start.elapsed();
+
+    let parsed = match parsed {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(err)) => return DayRun { day: puzzle.day(), input: input.to_path_buf(), parse_time, parts: Err(err.to_string()) },
+        Err(payload) => return DayRun { day: puzzle.day(), input: input.to_path_buf(), parse_time, parts: Err(panic_message(payload)) },
+    };
+
+    let parts = puzzle.parts().iter().map(|part| {
+        let start = Instant::now();
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| parsed.solve(*part)));
+        let time = start.elapsed();
+        let answer = match answer {
+            Ok(Some(Ok(answer))) => Ok(answer),
+            Ok(Some(Err(err))) => Err(err.in_file(&input.to_string_lossy()).to_string()),
+            Ok(None) => Err(format!("day {} has no part {}", puzzle.day(), part.number())),
+            Err(payload) => Err(panic_message(payload)),
+        };
+        PartRun { part: *part, answer, time }
+    }).collect();
+
+    DayRun { day: puzzle.day(), input: input.to_path_buf(), parse_time, parts: Ok(parts) }
+}
+
+// Runs every registered day which has an input file in inputs_dir, jobs days at a time. Days without one are None.
+pub fn run_all(inputs_dir: &Path, jobs: usize) -> Vec<(u8, Option<DayRun>)> {
+    let days: Vec<u8> = SOLUTIONS.iter().map(|puzzle| puzzle.day()).collect();
+    run_days(inputs_dir, &days, jobs)
+}
+
+// For callers which catch panics and keep the message in their report, so it isn't also printed to stderr
+pub fn without_panic_output<T>(f: impl FnOnce() -> T) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = f();
+    panic::set_hook(hook);
+    result
+}
+
+// The runs are in day order whatever order they finish in
+pub fn run_days(inputs_dir: &Path, days: &[u8], jobs: usize) -> Vec<(u8, Option<DayRun>)> {
+    let puzzles: Vec<&dyn Puzzle> = SOLUTIONS.iter().filter(|puzzle| days.contains(&puzzle.day())).copied().collect();
+    without_panic_output(|| {
+        parallel_map(&puzzles, jobs, |puzzle| {
+            let input = input_path(inputs_dir, puzzle.day());
+            if input.is_file() {
+                (puzzle.day(), Some(run_day(*puzzle, &input)))
+            } else {
+                (puzzle.day(), None)
+            }
+        })
+    })
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{:.2}s", secs)
+    } else if secs >= 0.001 {
+        format!("{:.1}ms", secs * 1e3)
+    } else {
+        format!("{:.1}µs", secs * 1e6)
+    }
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_millis(2500)), "2.50s");
+    assert_eq!(format_duration(Duration::from_micros(12340)), "12.3ms");
+    assert_eq!(format_duration(Duration::from_nanos(4560)), "4.6µs");
+}
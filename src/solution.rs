@@ -0,0 +1,334 @@
+use std::any::Any;
+use std::fmt;
+use std::io::Read;
+use crate::animate::OnFrame;
+use crate::common::{input_name, read_all, read_input};
+use crate::error::ParseError;
+use crate::params::{self, ParamError, Params};
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13,
+            day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    // the answer along with how it was worked out, which only running a single day prints
+    Explained(String, Box<Answer>),
+}
+
+impl Answer {
+    pub fn explained(self, explanation: String) -> Answer {
+        Answer::Explained(explanation, Box::new(self))
+    }
+
+    pub fn explanation(&self) -> Option<&str> {
+        match self {
+            Answer::Explained(explanation, _answer) => Some(explanation),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::UInt(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Explained(_explanation, answer) => write!(f, "{}", answer),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Int(n)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Answer {
+        Answer::UInt(n)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Answer {
+        Answer::UInt(n as u64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::UInt(n as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn from_number(n: u16) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None
+        }
+    }
+
+    pub fn number(self) -> u16 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+// A day's puzzle. The input is parsed once and then shared by both parts.
+pub trait Solution {
+    const DAY: u8;
+    const PARTS: &'static [Part] = &[Part::One, Part::Two];
+    // whether animate plays the simulation
+    const ANIMATED: bool = false;
+
+    type Input: 'static;
+    // () for days which have nothing to tweak
+    type Config: params::Config;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError>;
+
+    fn parse_reader(reader: impl Read) -> Result<Self::Input, ParseError> where Self: Sized {
+        Self::parse(&read_all(reader, "input")?)
+    }
+
+    // Err for input which parses but which the part can't solve, like day 22's part 2 on a board which isn't a cube net
+    fn part1(input: &Self::Input, config: &Self::Config) -> Result<Answer, ParseError>;
+
+    fn part2(_input: &Self::Input, _config: &Self::Config) -> Result<Answer, ParseError> {
+        panic!("day {} has no part 2", Self::DAY)
+    }
+
+    // Simulations can be watched, calling on_frame after every step. None for days which can't.
+    fn animate(_input: &Self::Input, _config: &Self::Config, _part: Part, _on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        None
+    }
+}
+
+// Object safe view of a Solution so that every day can live in the same registry
+pub trait Puzzle: Sync {
+    fn day(&self) -> u8;
+
+    fn parts(&self) -> &'static [Part];
+
+    fn param_keys(&self) -> &'static [&'static str];
+
+    fn animated(&self) -> bool;
+
+    fn config(&self, params: &Params) -> Result<Box<dyn Any>, ParamError>;
+
+    fn parse(&self, contents: &str) -> Result<Parsed<'_>, ParseError>;
+
+    fn parse_reader(&self, reader: &mut dyn Read) -> Result<Parsed<'_>, ParseError> {
+        self.parse(&read_all(reader, "input")?)
+    }
+
+    // file_path can be "-" to read stdin
+    fn parse_file(&self, file_path: &str) -> Result<Parsed<'_>, ParseError> {
+        let contents = read_input(file_path)?;
+        self.parse(&contents).map_err(|err| err.in_file(input_name(file_path)))
+    }
+
+    fn solve(&self, input: &dyn Any, config: &dyn Any, part: Part) -> Option<Result<Answer, ParseError>>;
+
+    fn animate(&self, input: &dyn Any, config: &dyn Any, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>>;
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parts(&self) -> &'static [Part] {
+        S::PARTS
+    }
+
+    fn param_keys(&self) -> &'static [&'static str] {
+        <S::Config as params::Config>::KEYS
+    }
+
+    fn animated(&self) -> bool {
+        S::ANIMATED
+    }
+
+    fn config(&self, params: &Params) -> Result<Box<dyn Any>, ParamError> {
+        let config = <S::Config as params::Config>::from_params(params)?;
+        Ok(Box::new(config))
+    }
+
+    fn parse(&self, contents: &str) -> Result<Parsed<'_>, ParseError> {
+        let input = S::parse(contents)?;
+        Ok(Parsed {
+            puzzle: self,
+            input: Box::new(input),
+            config: Box::new(S::Config::default()),
+        })
+    }
+
+    fn solve(&self, input: &dyn Any, config: &dyn Any, part: Part) -> Option<Result<Answer, ParseError>> {
+        if !S::PARTS.contains(&part) {
+            return None;
+        }
+
+        let input = input.downcast_ref::<S::Input>().expect("input was parsed by a different day");
+        let config = config.downcast_ref::<S::Config>().expect("config was made by a different day");
+        Some(match part {
+            Part::One => S::part1(input, config),
+            Part::Two => S::part2(input, config),
+        })
+    }
+
+    fn animate(&self, input: &dyn Any, config: &dyn Any, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        if !S::PARTS.contains(&part) {
+            return None;
+        }
+
+        let input = input.downcast_ref::<S::Input>().expect("input was parsed by a different day");
+        let config = config.downcast_ref::<S::Config>().expect("config was made by a different day");
+        S::animate(input, config, part, on_frame)
+    }
+}
+
+pub struct Parsed<'a> {
+    puzzle: &'a dyn Puzzle,
+    input: Box<dyn Any>,
+    config: Box<dyn Any>,
+}
+
+impl Parsed<'_> {
+    // Replaces the default config, for example to run day 15 against the example's row 10
+    pub fn configure(&mut self, params: &Params) -> Result<(), ParamError> {
+        self.config = self.puzzle.config(params)?;
+        Ok(())
+    }
+
+    pub fn solve(&self, part: Part) -> Option<Result<Answer, ParseError>> {
+        self.puzzle.solve(self.input.as_ref(), self.config.as_ref(), part)
+    }
+
+    pub fn animate(&self, part: Part, on_frame: &mut OnFrame) -> Option<Result<Answer, ParseError>> {
+        self.puzzle.animate(self.input.as_ref(), self.config.as_ref(), part, on_frame)
+    }
+}
+
+pub static SOLUTIONS: [&dyn Puzzle; 25] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+pub fn get(day: u8) -> Option<&'static dyn Puzzle> {
+    SOLUTIONS.iter().find(|puzzle| puzzle.day() == day).copied()
+}
+
+#[test]
+fn test_registry() {
+    for (idx, puzzle) in SOLUTIONS.iter().enumerate() {
+        assert_eq!(puzzle.day() as usize, idx + 1);
+        assert_eq!(get(puzzle.day()).unwrap().day(), puzzle.day());
+    }
+    assert!(get(26).is_none());
+    assert_eq!(get(25).unwrap().parts(), &[Part::One]);
+}
+
+#[test]
+fn test_parse_contents() {
+    let contents = "1000\n2000\n\n3000\n";
+    let puzzle = get(1).unwrap();
+    assert_eq!(puzzle.parse(contents).unwrap().solve(Part::One), Some(Ok(Answer::UInt(3000))));
+    assert_eq!(puzzle.parse_reader(&mut contents.as_bytes()).unwrap().solve(Part::Two), Some(Ok(Answer::UInt(6000))));
+    assert_eq!(Answer::UInt(3000).explained("how\n".to_string()).to_string(), "3000");
+    assert_eq!(<day1::Day1 as Solution>::parse_reader(contents.as_bytes()).unwrap(), vec![vec![1000, 2000], vec![3000], vec![]]);
+}
+
+#[test]
+fn test_configure() {
+    let mut parsed = get(17).unwrap().parse(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>").unwrap();
+    assert_eq!(parsed.solve(Part::One), Some(Ok(Answer::Int(3068))));
+
+    let params: Params = [("part1_rocks".to_string(), "1".to_string())].into_iter().collect();
+    parsed.configure(&params).unwrap();
+    assert_eq!(parsed.solve(Part::One), Some(Ok(Answer::Int(1))));
+
+    let params: Params = [("part2_rocks".to_string(), "0".to_string())].into_iter().collect();
+    assert_eq!(parsed.configure(&params).unwrap_err().to_string(), "invalid value \"0\" for part2_rocks, expected a number of rocks from 1 up");
+
+    let params: Params = [("rocks".to_string(), "1".to_string())].into_iter().collect();
+    assert_eq!(parsed.configure(&params).unwrap_err().to_string(), "unknown parameter \"rocks\", expected one of part1_rocks, part2_rocks");
+    assert_eq!(get(1).unwrap().param_keys(), &[] as &[&str]);
+}
+
+#[test]
+fn test_parse_errors() {
+    // inputs which used to get through parsing and then panic
+    for (day, contents, expected) in [
+        (5, "[A] [B]\n 1   2 \n\nmove 2 from 1 to 2\n", "line 4, column 6: expected at most the number of crates left on stack 1, which is 1, found \"2\""),
+        (11, "Monkey 0:\n  Starting items: 1\n  Operation: new = old * 2\n  Test: divisible by 0\n    If true: throw to monkey 0\n    If false: throw to monkey 0\n", "line 4, column 22: expected a divisor above 0, found \"0\""),
+        (11, "Monkey 0:\n  Starting items: 1\n  Operation: new = old * 2\n  Test: divisible by 2\n    If true: throw to monkey 0\n    If false: throw to monkey 0\n", "line 7, column 1: expected notes for monkey 1, found end of input"),
+        (14, "", "line 1, column 1: expected a path of rock like 498,4 -> 498,6, found end of input"),
+        (14, "500,0 -> 500,0\n", "line 1, column 10: expected a path of rock which doesn't cover the sand source at 500,0, found \"500,0\""),
+        (18, "\n", "line 1, column 1: expected a cube like 2,2,2, found end of input"),
+        (20, "0\n", "line 2, column 1: expected a second number to mix, found end of input"),
+        (21, "root: aaaa + root\naaaa: 1\nhumn: 5\n", "line 1, column 14: expected a monkey which isn't waiting for root, found \"root\""),
+        (21, "root: 1\nroot: 2\n", "line 2, column 1: expected a monkey which isn't listed already, found \"root\""),
+        (21, "root: aaaa / bbbb\naaaa: 1\nbbbb: 0\n", "line 1, column 14: expected a monkey which doesn't yell 0 to divide by, found \"bbbb\""),
+    ] {
+        let err = get(day).unwrap().parse(contents).err().map(|err| err.to_string());
+        assert_eq!(err.as_deref(), Some(expected), "day {}", day);
+    }
+
+    // inputs which part 1 can solve but part 2 can't
+    for (day, contents, part1, expected) in [
+        (6, "abcdxxxxxxxx", Answer::UInt(4), "line 1, column 13: expected 14 different characters in a row for the start of message marker, found end of input"),
+        (21, "root: aaaa + bbbb\naaaa: 1\nbbbb: 2\n", Answer::Int(3), "line 4, column 1: expected a monkey named humn, found end of input"),
+        (21, "root: 1\nhumn: 2\n", Answer::Int(1), "line 1, column 1: expected a root which waits for two monkeys, found \"root: 1\""),
+        (21, "root: aaaa + bbbb\naaaa: 1\nbbbb: 2\nhumn: 5\n", Answer::Int(3), "line 4, column 1: expected a humn which root waits for, found \"humn: 5\""),
+        (22, "...\n...\n\n1\n", Answer::Int(1008), "line 2, column 2: expected a square which folds onto a side of the cube no other square covers, found \".\""),
+    ] {
+        let parsed = get(day).unwrap().parse(contents).unwrap();
+        assert_eq!(parsed.solve(Part::One), Some(Ok(part1)), "day {}", day);
+        let err = parsed.solve(Part::Two).unwrap().err().map(|err| err.to_string());
+        assert_eq!(err.as_deref(), Some(expected), "day {}", day);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde_json::{Map, Value};
+use crate::runner::DayRun;
+use crate::solution::Part;
+
+// Expected answers keyed by day and then part, stored as JSON like {"1": {"1": "24000", "2": "45000"}}
+pub type Manifest = BTreeMap<u8, BTreeMap<u16, String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Error(String),
+    // the solver ran but there's nothing in the manifest to compare against
+    Unknown(String),
+}
+
+impl Verdict {
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error(_))
+    }
+}
+
+pub fn parse_manifest(contents: &str) -> Result<Manifest, String> {
+    let value: Value = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+    let days = value.as_object().ok_or("expected an object keyed by day")?;
+
+    let mut manifest = Manifest::new();
+    for (day_key, parts) in days {
+        let day: u8 = day_key.parse().map_err(|_| format!("expected a day number, found {:?}", day_key))?;
+        let parts = parts.as_object().ok_or_else(|| format!("expected an object keyed by part for day {}", day))?;
+        for (part_key, answer) in parts {
+            let part: u16 = part_key.parse().map_err(|_| format!("expected a part number for day {}, found {:?}", day, part_key))?;
+            let answer = match answer {
+                Value::String(answer) => answer.clone(),
+                Value::Number(answer) => answer.to_string(),
+                _ => return Err(format!("expected a string or number answer for day {} part {}", day, part)),
+            };
+            manifest.entry(day).or_default().insert(part, answer);
+        }
+    }
+    Ok(manifest)
+}
+
+pub fn load_manifest(path: &Path) -> Result<Manifest, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    parse_manifest(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+pub fn manifest_to_json(manifest: &Manifest) -> String {
+    let days: Map<String, Value> = manifest.iter().map(|(day, parts)| {
+        let parts: Map<String, Value> = parts.iter().map(|(part, answer)| (part.to_string(), Value::String(answer.clone()))).collect();
+        (day.to_string(), Value::Object(parts))
+    }).collect();
+    serde_json::to_string_pretty(&Value::Object(days)).unwrap()
+}
+
+pub fn verify_day(run: &DayRun, manifest: &Manifest) -> Vec<(Part, Verdict)> {
+    let expected = manifest.get(&run.day);
+    match &run.parts {
+        Err(err) => {
+            // every part we expected an answer for is an error
+            let parts: Vec<Part> = expected.map(|parts| parts.keys().filter_map(|part| Part::from_number(*part)).collect()).unwrap_or_default();
+            parts.into_iter().map(|part| (part, Verdict::Error(err.clone()))).collect()
+        },
+        Ok(parts) => parts.iter().map(|part| {
+            let verdict = match (&part.answer, expected.and_then(|parts| parts.get(&part.part.number()))) {
+                (Err(err), _) => Verdict::Error(err.clone()),
+                (Ok(answer), None) => Verdict::Unknown(answer.to_string()),
+                (Ok(answer), Some(expected)) => {
+                    let actual = answer.to_string();
+                    // multi line answers are compared without the surrounding blank lines
+                    if actual.trim() == expected.trim() {
+                        Verdict::Pass
+                    } else {
+                        Verdict::Fail { expected: expected.clone(), actual }
+                    }
+                }
+            };
+            (part.part, verdict)
+        }).collect()
+    }
+}
+
+#[test]
+fn test_manifest() {
+    let manifest = parse_manifest(r#"{"1": {"1": 24000, "2": "45000"}, "25": {"1": "2=-1=0"}}"#).unwrap();
+    assert_eq!(manifest[&1][&1], "24000");
+    assert_eq!(manifest[&1][&2], "45000");
+    assert_eq!(manifest[&25][&1], "2=-1=0");
+    assert_eq!(parse_manifest(&manifest_to_json(&manifest)).unwrap(), manifest);
+    assert!(parse_manifest(r#"{"one": {}}"#).is_err());
+}